@@ -5,19 +5,20 @@ use system_theme::SystemTheme;
 async fn main() {
     let theme = SystemTheme::new().unwrap();
 
-    println!("Theme: {:?}", theme.get_theme());
+    println!("Theme: {:?}", theme.get_theme_async().await);
 
-    let subscription = theme.subscribe();
+    let subscription = theme.subscribe_changes();
     pin_mut!(subscription);
 
     loop {
         println!("Waiting for theme change...");
 
-        if let None = subscription.next().await {
+        let Some(change) = subscription.next().await else {
             println!("No more theme changes");
             break;
-        }
+        };
 
-        println!("Theme: {:?}", theme.get_theme());
+        println!("Change: {:?}", change);
+        println!("Theme: {:?}", theme.get_theme_async().await);
     }
 }
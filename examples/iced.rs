@@ -51,14 +51,11 @@ impl ThemeApp {
     }
 
     fn update(&mut self, message: Message) {
-        match message {
-            Message::ThemeChanged => {
-                // Get the new system theme
-                self.sys_theme.as_ref().map(|sys_theme| {
-                    self.theme = sys_theme.get_theme().into();
-                });
+        if let Message::ThemeChanged = message {
+            // Get the new system theme
+            if let Some(sys_theme) = &self.sys_theme {
+                self.theme = sys_theme.get_theme().into();
             }
-            _ => {} // Do nothing
         }
     }
 
@@ -2,10 +2,19 @@ use system_theme::SystemTheme;
 
 fn main() {
     let theme = SystemTheme::new().unwrap();
+    println!("Desktop: {:?}", theme.get_desktop());
     println!("Theme kind: {:?}", theme.get_kind());
     println!("Theme scheme: {:?}", theme.get_scheme());
+    println!(
+        "Theme scheme preference: {:?}",
+        theme.get_scheme_preference()
+    );
     println!("Theme contrast: {:?}", theme.get_contrast());
     println!("Theme accent: {:?}", theme.get_accent());
+    println!("Theme accent shades: {:?}", theme.get_accent_shades());
+    println!("Theme motion: {:?}", theme.get_motion());
+    println!("Theme fonts: {:?}", theme.get_fonts());
+    println!("Theme scaling: {:?}", theme.get_scaling());
 
     // Full theme (with fallbacks for unsupported methods)
     println!("Theme: {:?}", theme.get_theme());
@@ -0,0 +1,42 @@
+//! Desktop environment definitions
+
+/// Desktop environment (or window manager) running the application
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Desktop {
+    /// Windows shell
+    Windows,
+    /// macOS
+    MacOS,
+    /// GNOME
+    Gnome,
+    /// KDE Plasma
+    Kde,
+    /// COSMIC
+    Cosmic,
+    /// Xfce
+    Xfce,
+    /// Cinnamon
+    Cinnamon,
+    /// MATE
+    Mate,
+    /// LXQt
+    Lxqt,
+    /// LXDE
+    Lxde,
+    /// Budgie
+    Budgie,
+    /// Pantheon (elementary OS)
+    Pantheon,
+    /// Unity
+    Unity,
+    /// Deepin
+    Deepin,
+    /// Hyprland
+    Hyprland,
+    /// Sway
+    Sway,
+    /// Desktop could not be identified
+    Unknown,
+}
@@ -2,7 +2,10 @@
 use std::fmt::Display;
 
 /// Error type for system theme operations.
+///
+/// New variants can be added in minor releases, so matches need a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// Operation not supported by the platform.
     Unsupported,
@@ -10,6 +13,8 @@ pub enum Error {
     Unavailable,
     /// Main thread required error.
     MainThreadRequired,
+    /// Invalid value or file content (with a description of the problem).
+    Parse(String),
     /// Internal platform error.
     Platform(Box<dyn std::error::Error + Send + Sync>),
 }
@@ -20,6 +25,7 @@ impl Display for Error {
             Error::Unsupported => write!(f, "Unsupported operation"),
             Error::Unavailable => write!(f, "Unavailable data"),
             Error::MainThreadRequired => write!(f, "Main thread required"),
+            Error::Parse(err) => write!(f, "Parse error: {}", err),
             Error::Platform(err) => write!(f, "Platform error: {}", err),
         }
     }
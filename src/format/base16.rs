@@ -0,0 +1,142 @@
+//! base16 and base24 schemes (YAML)
+//!
+//! Both the original layout (`scheme` and top level `baseXX` keys) and the newer one (`name` and a
+//! `palette` map) are read. Only flat `key: value` lines are needed, so YAML is not fully parsed.
+use std::collections::HashMap;
+
+use super::{RoleKeys, Roles};
+use crate::error::Error;
+
+/// Palette roles and their base colors (following the base16 styling guidelines)
+///
+/// Source: https://github.com/chriskempson/base16/blob/main/styling.md
+const ROLE_KEYS: &RoleKeys = &[
+    ("background", &["base00"]),
+    ("foreground", &["base05"]),
+    ("view", &["base00"]),
+    // base24 darker backgrounds
+    ("sidebar", &["base10", "base01"]),
+    ("headerbar", &["base11", "base01"]),
+    ("card", &["base01"]),
+    ("border", &["base02"]),
+    ("muted_foreground", &["base04"]),
+    ("disabled_foreground", &["base03"]),
+    ("accent", &["base0D"]),
+    ("selection", &["base02"]),
+    ("selection_foreground", &["base05"]),
+    ("focus_ring", &["base0D"]),
+    ("link", &["base0D"]),
+    ("visited_link", &["base0E"]),
+    ("tooltip", &["base01"]),
+    ("tooltip_foreground", &["base05"]),
+    ("success", &["base0B"]),
+    ("warning", &["base0A"]),
+    ("danger", &["base08"]),
+];
+
+/// Get the value of a `key: value` line, unquoted and without comment
+fn parse_value(value: &str) -> &str {
+    let value = value.trim();
+
+    for quote in ['"', '\''] {
+        if let Some(value) = value.strip_prefix(quote) {
+            return value.split(quote).next().unwrap_or_default();
+        }
+    }
+
+    value.split(" #").next().unwrap_or_default().trim()
+}
+
+/// Parse a scheme, returns its name and palette roles.
+pub(crate) fn parse(content: &str) -> Result<(Option<String>, Roles), Error> {
+    let mut values = HashMap::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once(':') {
+            values.insert(key.trim(), parse_value(value));
+        }
+    }
+
+    // Hex colors have no `#` in the original layout
+    let roles = super::map_roles(ROLE_KEYS, |key| {
+        let Some(value) = values.get(key) else {
+            return Ok(None);
+        };
+        let value = value.strip_prefix('#').unwrap_or(value);
+
+        super::parse_color(key, &format!("#{}", value)).map(Some)
+    })?;
+
+    let name = values
+        .get("name")
+        .or_else(|| values.get("scheme"))
+        .map(|name| name.to_string());
+
+    Ok((name, roles))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{format::tests::role, ThemeFormat, ThemeImport};
+
+    fn import(content: &str) -> ThemeImport {
+        ThemeImport::parse(content, ThemeFormat::Base16).unwrap()
+    }
+
+    #[test]
+    fn default_dark() {
+        let import = import(include_str!("fixtures/default-dark.yaml"));
+
+        assert_eq!(import.theme.name, "Default Dark");
+        let expected = [
+            ("background", "#181818"),
+            ("foreground", "#D8D8D8"),
+            ("view", "#181818"),
+            ("sidebar", "#282828"),
+            ("headerbar", "#282828"),
+            ("card", "#282828"),
+            ("border", "#383838"),
+            ("muted_foreground", "#B8B8B8"),
+            ("disabled_foreground", "#585858"),
+            ("accent", "#7CAFC2"),
+            ("selection", "#383838"),
+            ("selection_foreground", "#D8D8D8"),
+            ("focus_ring", "#7CAFC2"),
+            ("link", "#7CAFC2"),
+            ("visited_link", "#BA8BAF"),
+            ("tooltip", "#282828"),
+            ("tooltip_foreground", "#D8D8D8"),
+            ("success", "#A1B56C"),
+            ("warning", "#F7CA88"),
+            ("danger", "#AB4642"),
+        ];
+        for (name, color) in expected {
+            assert_eq!(role(&import, name), color, "{}", name);
+        }
+
+        assert_eq!(import.defaulted, ["accent_foreground"]);
+    }
+
+    #[test]
+    fn palette_layout() {
+        let original = import(include_str!("fixtures/default-dark.yaml"));
+        let palette = import(include_str!("fixtures/default-dark-palette.yaml"));
+
+        assert_eq!(palette, original);
+    }
+
+    #[test]
+    fn base24_backgrounds() {
+        let content = "scheme: \"Base24\"\nbase00: \"282a36\"\nbase01: \"363447\"\nbase10: \"1e1f29\"\nbase11: \"0e0f15\"\n";
+        let import = import(content);
+
+        assert_eq!(role(&import, "sidebar"), "#1E1F29");
+        assert_eq!(role(&import, "headerbar"), "#0E0F15");
+        assert_eq!(role(&import, "card"), "#363447");
+    }
+}
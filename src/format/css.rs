@@ -0,0 +1,97 @@
+//! CSS custom properties and SCSS variables
+use crate::{Theme, ThemeColor, ThemeScheme};
+
+/// Get the CSS `color-scheme` of a theme
+fn color_scheme(theme: &Theme) -> &'static str {
+    match theme.palette.scheme() {
+        ThemeScheme::Light => "light",
+        ThemeScheme::Dark => "dark",
+    }
+}
+
+/// Get the palette colors with their variable names (e.g. `accent-light1`)
+fn variables(theme: &Theme) -> Vec<(String, ThemeColor)> {
+    let palette = &theme.palette;
+    let mut variables = Vec::new();
+
+    for (role, color) in palette.roles() {
+        variables.push((role.replace('_', "-"), color));
+
+        // Shades are kept next to the accent
+        if role == "accent" {
+            for (shade, color) in palette.accent_shades.shades() {
+                variables.push((format!("accent-{}", shade), color));
+            }
+        }
+    }
+
+    variables
+}
+
+/// Export a theme to CSS custom properties.
+pub(crate) fn export_css(theme: &Theme) -> String {
+    let properties: String = variables(theme)
+        .into_iter()
+        .map(|(name, color)| format!("  --{}: {};\n", name, color))
+        .collect();
+
+    format!(
+        "/* {} */\n:root {{\n  color-scheme: {};\n{}}}\n",
+        super::comment(&theme.name),
+        color_scheme(theme),
+        properties
+    )
+}
+
+/// Export a theme to SCSS variables.
+pub(crate) fn export_scss(theme: &Theme) -> String {
+    let variables: String = variables(theme)
+        .into_iter()
+        .map(|(name, color)| format!("${}: {};\n", name, color))
+        .collect();
+
+    format!(
+        "// {}\n$color-scheme: {};\n{}",
+        super::comment(&theme.name),
+        color_scheme(theme),
+        variables
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ThemeContrast, ThemeKind};
+
+    fn theme(scheme: ThemeScheme) -> Theme {
+        Theme::new(ThemeKind::Gtk, scheme, ThemeContrast::Normal, None)
+    }
+
+    #[test]
+    fn css_export() {
+        let css = export_css(&theme(ThemeScheme::Light));
+        assert_eq!(css, include_str!("fixtures/export-adwaita-light.css"));
+    }
+
+    #[test]
+    fn scss_export() {
+        let scss = export_scss(&theme(ThemeScheme::Light));
+        assert_eq!(scss, include_str!("fixtures/export-adwaita-light.scss"));
+    }
+
+    #[test]
+    fn dark_color_scheme() {
+        let theme = theme(ThemeScheme::Dark);
+        assert!(export_css(&theme).contains("\n  color-scheme: dark;\n"));
+        assert!(export_scss(&theme).contains("\n$color-scheme: dark;\n"));
+    }
+
+    #[test]
+    fn translucent_colors() {
+        // CSS has alpha, so colors are kept as they are
+        let mut theme = theme(ThemeScheme::Light);
+        theme.palette.selection = ThemeColor::from_rgb8(53, 132, 228).with_alpha(0.5);
+        assert!(export_css(&theme).contains("\n  --selection: #3584E480;\n"));
+        assert!(export_scss(&theme).contains("\n$selection: #3584E480;\n"));
+    }
+}
@@ -0,0 +1,453 @@
+//! GTK and libadwaita CSS (`@define-color` declarations)
+//!
+//! Colors can reference other ones (`@name`) and use the GTK color functions (`shade`, `alpha`,
+//! `mix`, `lighter` and `darker`).
+use std::collections::HashMap;
+
+use super::{RoleKeys, Roles};
+use crate::{error::Error, theme::ROLES, Hsl, Theme, ThemeColor};
+
+const DEFINE_COLOR: &str = "@define-color";
+
+/// Maximum depth of color references (guards against reference cycles)
+const MAX_DEPTH: usize = 32;
+/// Shade factors of the `lighter` and `darker` functions
+const LIGHTER_FACTOR: f32 = 1.3;
+const DARKER_FACTOR: f32 = 0.7;
+
+/// Palette roles and their color names, libadwaita ones first then GTK 3 ones
+const ROLE_KEYS: &RoleKeys = &[
+    (
+        "background",
+        &["window_bg_color", "theme_bg_color", "bg_color"],
+    ),
+    (
+        "foreground",
+        &["window_fg_color", "theme_fg_color", "fg_color"],
+    ),
+    ("view", &["view_bg_color", "theme_base_color", "base_color"]),
+    ("sidebar", &["sidebar_bg_color"]),
+    ("headerbar", &["headerbar_bg_color"]),
+    ("card", &["card_bg_color"]),
+    ("border", &["borders"]),
+    // Not a libadwaita color, it's only written by the export
+    ("muted_foreground", &["muted_fg_color"]),
+    ("disabled_foreground", &["insensitive_fg_color"]),
+    (
+        "accent",
+        &[
+            "accent_bg_color",
+            "theme_selected_bg_color",
+            "selected_bg_color",
+        ],
+    ),
+    (
+        "accent_foreground",
+        &[
+            "accent_fg_color",
+            "theme_selected_fg_color",
+            "selected_fg_color",
+        ],
+    ),
+    (
+        "selection",
+        &["theme_selected_bg_color", "selected_bg_color"],
+    ),
+    (
+        "selection_foreground",
+        &["theme_selected_fg_color", "selected_fg_color"],
+    ),
+    ("focus_ring", &["accent_color"]),
+    ("link", &["link_color", "accent_color"]),
+    (
+        "visited_link",
+        &["link_visited_color", "visited_link_color"],
+    ),
+    ("tooltip", &["theme_tooltip_bg_color", "tooltip_bg_color"]),
+    (
+        "tooltip_foreground",
+        &["theme_tooltip_fg_color", "tooltip_fg_color"],
+    ),
+    ("success", &["success_color", "success_bg_color"]),
+    ("warning", &["warning_color", "warning_bg_color"]),
+    (
+        "danger",
+        &["error_color", "destructive_color", "error_bg_color"],
+    ),
+];
+
+/// Color names the palette roles are exported to, in the order of `ROLES` (read first by the
+/// import)
+const EXPORT_NAMES: [&str; ROLES.len()] = [
+    "window_bg_color",
+    "window_fg_color",
+    "view_bg_color",
+    "sidebar_bg_color",
+    "headerbar_bg_color",
+    "card_bg_color",
+    "borders",
+    "muted_fg_color",
+    "insensitive_fg_color",
+    "accent_bg_color",
+    "accent_fg_color",
+    "theme_selected_bg_color",
+    "theme_selected_fg_color",
+    "accent_color",
+    "link_color",
+    "link_visited_color",
+    "theme_tooltip_bg_color",
+    "theme_tooltip_fg_color",
+    "success_color",
+    "warning_color",
+    "error_color",
+];
+
+/// Other libadwaita and GTK 3 colors, exported as references to the palette ones
+const EXPORT_ALIASES: &[(&str, &str)] = &[
+    ("view_fg_color", "window_fg_color"),
+    ("sidebar_fg_color", "window_fg_color"),
+    ("headerbar_fg_color", "window_fg_color"),
+    ("card_fg_color", "window_fg_color"),
+    ("destructive_color", "error_color"),
+    ("theme_bg_color", "window_bg_color"),
+    ("theme_fg_color", "window_fg_color"),
+    ("theme_base_color", "view_bg_color"),
+    ("theme_text_color", "window_fg_color"),
+];
+
+/// Remove the CSS comments
+fn strip_comments(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(start) = rest.find("/*") {
+        result.push_str(&rest[..start]);
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Get the `@define-color` declarations (name and value)
+fn definitions(content: &str) -> HashMap<&str, &str> {
+    let mut definitions = HashMap::new();
+
+    for (index, _) in content.match_indices(DEFINE_COLOR) {
+        let declaration = &content[index + DEFINE_COLOR.len()..];
+        let declaration = declaration.split(';').next().unwrap_or_default().trim();
+
+        if let Some((name, value)) = declaration.split_once(char::is_whitespace) {
+            definitions.insert(name, value.trim());
+        }
+    }
+
+    definitions
+}
+
+/// Split the arguments of a function call, ignoring the commas of nested calls
+fn split_arguments(arguments: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+
+    for (index, char) in arguments.char_indices() {
+        match char {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                result.push(arguments[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    result.push(arguments[start..].trim());
+    result
+}
+
+/// Change the lightness and saturation of a color (like GTK `shade`)
+fn shade(color: ThemeColor, factor: f32) -> ThemeColor {
+    let hsl = color.to_hsl();
+
+    ThemeColor::from_hsl(Hsl {
+        saturation: (hsl.saturation * factor).clamp(0.0, 1.0),
+        lightness: (hsl.lightness * factor).clamp(0.0, 1.0),
+        ..hsl
+    })
+}
+
+/// Color expressions resolver
+struct Resolver<'a> {
+    definitions: HashMap<&'a str, &'a str>,
+}
+
+impl Resolver<'_> {
+    /// Resolve a color name, returns `None` if it's not defined
+    fn color(&self, name: &str) -> Result<Option<ThemeColor>, Error> {
+        match self.definitions.get(name) {
+            Some(value) => self.evaluate(name, value, 0).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Evaluate a color expression (the name is used for errors)
+    fn evaluate(&self, name: &str, value: &str, depth: usize) -> Result<ThemeColor, Error> {
+        let invalid = || Error::Parse(format!("invalid color for {}: {}", name, value));
+        if depth > MAX_DEPTH {
+            return Err(Error::Parse(format!("color reference loop in {}", name)));
+        }
+
+        // Reference to another color
+        if let Some(reference) = value.strip_prefix('@') {
+            let value = self.definitions.get(reference).ok_or_else(|| {
+                Error::Parse(format!("undefined color @{} in {}", reference, name))
+            })?;
+            return self.evaluate(reference, value, depth + 1);
+        }
+
+        let Some((function, arguments)) = value
+            .strip_suffix(')')
+            .and_then(|value| value.split_once('('))
+        else {
+            return super::parse_color(name, value);
+        };
+
+        let arguments = split_arguments(arguments);
+        let color = |index: usize| {
+            let argument: &str = arguments.get(index).ok_or_else(invalid)?;
+            self.evaluate(name, argument, depth + 1)
+        };
+        let number = |index: usize| {
+            arguments
+                .get(index)
+                .and_then(|argument| argument.parse::<f32>().ok())
+                .ok_or_else(invalid)
+        };
+
+        match function.trim() {
+            "shade" => Ok(shade(color(0)?, number(1)?)),
+            "lighter" => Ok(shade(color(0)?, LIGHTER_FACTOR)),
+            "darker" => Ok(shade(color(0)?, DARKER_FACTOR)),
+            "alpha" => {
+                let color = color(0)?;
+                Ok(color.with_alpha((color.alpha * number(1)?).clamp(0.0, 1.0)))
+            }
+            "mix" => Ok(color(0)?.mix(color(1)?, number(2)?)),
+            // CSS color functions (e.g. rgb)
+            _ => super::parse_color(name, value),
+        }
+    }
+}
+
+/// Parse a stylesheet, returns its name (never set) and palette roles.
+pub(crate) fn parse(content: &str) -> Result<(Option<String>, Roles), Error> {
+    let content = strip_comments(content);
+    let resolver = Resolver {
+        definitions: definitions(&content),
+    };
+
+    let roles = super::map_roles(ROLE_KEYS, |name| resolver.color(name))?;
+    Ok((None, roles))
+}
+
+/// Export a theme.
+pub(crate) fn export(theme: &Theme) -> String {
+    let colors = EXPORT_NAMES
+        .into_iter()
+        .zip(theme.palette.roles())
+        .map(|(name, (_, color))| (name, color.to_string()));
+    let aliases = EXPORT_ALIASES
+        .iter()
+        .map(|(alias, name)| (*alias, format!("@{}", name)));
+
+    let declarations: String = colors
+        .chain(aliases)
+        .map(|(name, value)| format!("{} {} {};\n", DEFINE_COLOR, name, value))
+        .collect();
+
+    format!("/* {} */\n{}", super::comment(&theme.name), declarations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{format::tests::role, ThemeFormat, ThemeImport};
+
+    fn resolver<'a>(definitions: &[(&'a str, &'a str)]) -> Resolver<'a> {
+        Resolver {
+            definitions: definitions.iter().copied().collect(),
+        }
+    }
+
+    fn evaluate(definitions: &[(&str, &str)], value: &str) -> Result<ThemeColor, Error> {
+        resolver(definitions).evaluate("test", value, 0)
+    }
+
+    #[test]
+    fn adwaita_dark() {
+        let content = include_str!("fixtures/adwaita-dark.css");
+        let import = ThemeImport::parse(content, ThemeFormat::Gtk).unwrap();
+
+        let background = ThemeColor::from_rgb8(0x24, 0x24, 0x24);
+        let expected = [
+            ("background", "#242424"),
+            ("foreground", "#FFFFFF"),
+            ("view", "#1E1E1E"),
+            ("sidebar", "#303030"),
+            ("headerbar", "#303030"),
+            ("accent", "#3584E4"),
+            ("accent_foreground", "#FFFFFF"),
+            ("selection", "#3584E4"),
+            ("selection_foreground", "#FFFFFF"),
+            ("focus_ring", "#78AEED"),
+            ("link", "#78AEED"),
+            ("success", "#8FF0A4"),
+            ("warning", "#F8E45C"),
+            ("danger", "#FF7B63"),
+        ];
+        for (name, color) in expected {
+            assert_eq!(role(&import, name), color, "{}", name);
+        }
+
+        // Translucent colors are flattened on the window background
+        let card = ThemeColor::WHITE
+            .with_alpha(0.08)
+            .composite_over(background);
+        assert_eq!(role(&import, "card"), card.to_string());
+        let disabled = ThemeColor::WHITE.with_alpha(0.5).composite_over(background);
+        assert_eq!(role(&import, "disabled_foreground"), disabled.to_string());
+        let border = background.mix(ThemeColor::WHITE, 0.15);
+        assert_eq!(role(&import, "border"), border.to_string());
+
+        assert_eq!(import.theme.name, "Custom");
+        assert_eq!(
+            import.defaulted,
+            [
+                "muted_foreground",
+                "visited_link",
+                "tooltip",
+                "tooltip_foreground"
+            ]
+        );
+    }
+
+    #[test]
+    fn references() {
+        let definitions = [("a", "@b"), ("b", "@c"), ("c", "#3584e4")];
+        assert_eq!(
+            evaluate(&definitions, "@a").unwrap(),
+            ThemeColor::from_rgb8(0x35, 0x84, 0xe4)
+        );
+
+        let resolver = resolver(&definitions);
+        assert!(resolver.color("a").unwrap().is_some());
+        assert!(resolver.color("undefined").unwrap().is_none());
+
+        let error = evaluate(&definitions, "@undefined").unwrap_err();
+        assert!(matches!(error, Error::Parse(message) if message.contains("@undefined")));
+    }
+
+    #[test]
+    fn reference_loops() {
+        let definitions = [("a", "@b"), ("b", "@a"), ("self", "@self")];
+        for value in ["@a", "@self", "shade(@a, 0.5)"] {
+            let error = evaluate(&definitions, value).unwrap_err();
+            assert!(matches!(error, Error::Parse(message) if message.contains("loop")));
+        }
+    }
+
+    /// Chain of references from `c0` to a color, `length` references deep
+    fn chain(length: usize) -> Vec<(String, String)> {
+        let mut chain: Vec<_> = (0..length)
+            .map(|index| (format!("c{}", index), format!("@c{}", index + 1)))
+            .collect();
+        chain.push((format!("c{}", length), "#ffffff".to_string()));
+        chain
+    }
+
+    #[test]
+    fn reference_depth() {
+        for (length, valid) in [(MAX_DEPTH, true), (MAX_DEPTH + 1, false)] {
+            let chain = chain(length);
+            let definitions: Vec<_> = chain
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .collect();
+
+            assert_eq!(
+                resolver(&definitions).color("c0").is_ok(),
+                valid,
+                "{}",
+                length
+            );
+        }
+    }
+
+    #[test]
+    fn functions() {
+        let gray = ThemeColor::from_rgb8(0x80, 0x80, 0x80);
+        let definitions = [("gray", "#808080"), ("black", "black"), ("white", "#fff")];
+
+        // Shade scales the lightness (and the saturation)
+        assert_eq!(evaluate(&definitions, "shade(@gray, 1.0)").unwrap(), gray);
+        let darker = evaluate(&definitions, "shade(@gray, 0.5)").unwrap();
+        assert_eq!(darker.to_string(), "#404040");
+        let lighter = evaluate(&definitions, "shade(@white, 2)").unwrap();
+        assert_eq!(lighter, ThemeColor::WHITE);
+        assert_eq!(
+            evaluate(&definitions, "darker(@gray)").unwrap(),
+            shade(gray, DARKER_FACTOR)
+        );
+        assert_eq!(
+            evaluate(&definitions, "lighter(@gray)").unwrap(),
+            shade(gray, LIGHTER_FACTOR)
+        );
+        let red = shade(ThemeColor::from_rgb8(0xff, 0, 0), 0.5).to_hsl();
+        assert_eq!(red.saturation, 0.5);
+        assert_eq!(red.lightness, 0.25);
+
+        // Mix goes from the first color to the second one
+        let mix = evaluate(&definitions, "mix(@black, @white, 0.25)").unwrap();
+        assert_eq!(mix.to_string(), "#404040");
+        assert_eq!(
+            evaluate(&definitions, "mix(@black, @white, 0)").unwrap(),
+            ThemeColor::BLACK
+        );
+
+        // Alpha multiplies the current alpha
+        let alpha = evaluate(&definitions, "alpha(@white, 0.5)").unwrap();
+        assert_eq!(alpha.alpha, 0.5);
+        let alpha = evaluate(&definitions, "alpha(alpha(@white, 0.5), 0.5)").unwrap();
+        assert_eq!(alpha.alpha, 0.25);
+        let alpha = evaluate(&definitions, "alpha(@white, 3)").unwrap();
+        assert_eq!(alpha.alpha, 1.0);
+
+        // Nested calls and CSS functions
+        let nested = evaluate(&definitions, "mix(shade(@white, 0.5), rgb(0, 0, 0), 0.5)").unwrap();
+        assert_eq!(nested.to_string(), "#404040");
+    }
+
+    #[test]
+    fn invalid_functions() {
+        let definitions = [("white", "#fff")];
+        for value in [
+            "shade(@white)",
+            "shade(@white, bright)",
+            "mix(@white)",
+            "alpha()",
+        ] {
+            assert!(evaluate(&definitions, value).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn comments() {
+        let content = "/* @define-color window_bg_color #000000; */\n@define-color window_bg_color /* dark */ #242424;";
+        let import = ThemeImport::parse(content, ThemeFormat::Gtk).unwrap();
+        assert_eq!(role(&import, "background"), "#242424");
+    }
+}
@@ -0,0 +1,454 @@
+//! Minimal JSON parser, with the JSONC extensions used by VS Code (comments and trailing commas)
+//!
+//! Themes are exported and read back with the layout of their `serde` serialization.
+use std::{iter::Peekable, str::CharIndices};
+
+use super::Layout;
+use crate::{
+    error::Error,
+    theme::{ROLES, SHADES},
+    Theme,
+};
+
+/// JSON value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Object members, in order
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Parse JSON content.
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        let mut parser = Parser {
+            content,
+            chars: content.char_indices().peekable(),
+        };
+
+        let value = parser.value()?;
+        parser.skip_whitespace()?;
+        match parser.chars.peek() {
+            Some(&(index, _)) => Err(parser.error(index, "unexpected content")),
+            None => Ok(value),
+        }
+    }
+
+    /// Get the value of an object member.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Get the string value.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Get the object members with a string value.
+    fn strings(&self) -> Vec<(&str, &str)> {
+        match self {
+            Json::Object(members) => members
+                .iter()
+                .filter_map(|(name, value)| Some((name.as_str(), value.as_str()?)))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+struct Parser<'a> {
+    content: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl Parser<'_> {
+    /// Create an error at a byte index (reported as line and column)
+    fn error(&self, index: usize, message: &str) -> Error {
+        let before = &self.content[..index];
+        let line = before.matches('\n').count() + 1;
+        let column = before.len() - before.rfind('\n').map_or(0, |index| index + 1) + 1;
+
+        Error::Parse(format!("{} at line {}, column {}", message, line, column))
+    }
+
+    /// Create an error at the current position
+    fn error_here(&mut self, message: &str) -> Error {
+        let index = self
+            .chars
+            .peek()
+            .map_or(self.content.len(), |&(index, _)| index);
+        self.error(index, message)
+    }
+
+    /// Skip whitespace and comments
+    fn skip_whitespace(&mut self) -> Result<(), Error> {
+        while let Some(&(index, char)) = self.chars.peek() {
+            if char.is_whitespace() {
+                self.chars.next();
+                continue;
+            }
+
+            if char != '/' {
+                break;
+            }
+
+            let rest = &self.content[index..];
+            if rest.starts_with("//") {
+                while self.chars.next_if(|&(_, char)| char != '\n').is_some() {}
+            } else if let Some(comment) = rest.strip_prefix("/*") {
+                let end = comment
+                    .find("*/")
+                    .ok_or_else(|| self.error(index, "unterminated comment"))?;
+                while self
+                    .chars
+                    .next_if(|&(position, _)| position < index + 2 + end + 2)
+                    .is_some()
+                {}
+            } else {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Consume an expected character
+    fn expect(&mut self, expected: char) -> Result<(), Error> {
+        self.skip_whitespace()?;
+        match self.chars.next_if(|&(_, char)| char == expected) {
+            Some(_) => Ok(()),
+            None => Err(self.error_here(&format!("expected '{}'", expected))),
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, Error> {
+        self.skip_whitespace()?;
+
+        match self.chars.peek().map(|&(_, char)| char) {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => self.string().map(Json::String),
+            Some('t') => self.keyword("true", Json::Bool(true)),
+            Some('f') => self.keyword("false", Json::Bool(false)),
+            Some('n') => self.keyword("null", Json::Null),
+            Some(char) if char == '-' || char.is_ascii_digit() => self.number(),
+            Some(_) => Err(self.error_here("unexpected character")),
+            None => Err(self.error_here("unexpected end of content")),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Json) -> Result<Json, Error> {
+        for expected in keyword.chars() {
+            if self.chars.next_if(|&(_, char)| char == expected).is_none() {
+                return Err(self.error_here(&format!("expected '{}'", keyword)));
+            }
+        }
+
+        Ok(value)
+    }
+
+    fn number(&mut self) -> Result<Json, Error> {
+        let start = self.chars.peek().map_or(0, |&(index, _)| index);
+        let mut end = start;
+        while let Some((index, char)) = self
+            .chars
+            .next_if(|&(_, char)| char.is_ascii_digit() || "+-.eE".contains(char))
+        {
+            end = index + char.len_utf8();
+        }
+
+        self.content[start..end]
+            .parse()
+            .map(Json::Number)
+            .map_err(|_| self.error(start, "invalid number"))
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.expect('"')?;
+        let mut string = String::new();
+
+        loop {
+            let Some((index, char)) = self.chars.next() else {
+                return Err(self.error_here("unterminated string"));
+            };
+
+            match char {
+                '"' => return Ok(string),
+                '\\' => {
+                    let escaped = match self.chars.next().map(|(_, char)| char) {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.unicode_escape()?,
+                        _ => return Err(self.error(index, "invalid escape")),
+                    };
+                    string.push(escaped);
+                }
+                _ => string.push(char),
+            }
+        }
+    }
+
+    /// Parse the code of a `\u` escape (with surrogate pairs)
+    fn unicode_escape(&mut self) -> Result<char, Error> {
+        let code = self.hex_code()?;
+
+        if (0xD800..0xDC00).contains(&code) && self.content[self.position()..].starts_with("\\u") {
+            self.chars.next();
+            self.chars.next();
+            let low = self.hex_code()?;
+            let code = 0x10000 + ((code - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+            return Ok(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+        }
+
+        Ok(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    /// Parse 4 hexadecimal digits
+    fn hex_code(&mut self) -> Result<u32, Error> {
+        let start = self.position();
+        let digits = self.content.get(start..start + 4).unwrap_or_default();
+        if digits.len() != 4 || !digits.chars().all(|char| char.is_ascii_hexdigit()) {
+            return Err(self.error(start, "invalid unicode escape"));
+        }
+
+        for _ in 0..4 {
+            self.chars.next();
+        }
+        u32::from_str_radix(digits, 16).map_err(|_| self.error(start, "invalid unicode escape"))
+    }
+
+    /// Get the current byte index
+    fn position(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map_or(self.content.len(), |&(index, _)| index)
+    }
+
+    fn array(&mut self) -> Result<Json, Error> {
+        self.expect('[')?;
+        let mut values = Vec::new();
+
+        loop {
+            self.skip_whitespace()?;
+            if self.chars.next_if(|&(_, char)| char == ']').is_some() {
+                return Ok(Json::Array(values));
+            }
+
+            values.push(self.value()?);
+            self.skip_whitespace()?;
+            if self.chars.next_if(|&(_, char)| char == ',').is_none() {
+                self.expect(']')?;
+                return Ok(Json::Array(values));
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, Error> {
+        self.expect('{')?;
+        let mut members = Vec::new();
+
+        loop {
+            self.skip_whitespace()?;
+            if self.chars.next_if(|&(_, char)| char == '}').is_some() {
+                return Ok(Json::Object(members));
+            }
+
+            let key = self.string()?;
+            self.expect(':')?;
+            members.push((key, self.value()?));
+            self.skip_whitespace()?;
+            if self.chars.next_if(|&(_, char)| char == ',').is_none() {
+                self.expect('}')?;
+                return Ok(Json::Object(members));
+            }
+        }
+    }
+}
+
+/// Parse a quoted string at the start of some content, returns it with the content left
+pub(crate) fn unquote(content: &str) -> Result<(String, &str), Error> {
+    let mut parser = Parser {
+        content,
+        chars: content.char_indices().peekable(),
+    };
+
+    let string = parser.string()?;
+    let position = parser.position();
+    Ok((string, &content[position..]))
+}
+
+/// Quote and escape a string (also a valid TOML basic string)
+pub(crate) fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+
+    for char in value.chars() {
+        match char {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            char if char.is_control() => quoted.push_str(&format!("\\u{:04x}", char as u32)),
+            char => quoted.push(char),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+/// Export a theme.
+pub(crate) fn export(theme: &Theme) -> String {
+    let palette = &theme.palette;
+    let mut members = Vec::new();
+
+    for (role, color) in palette.roles() {
+        members.push(format!("    \"{}\": \"{}\"", role, color));
+
+        if role == "accent" {
+            let shades: Vec<_> = palette
+                .accent_shades
+                .shades()
+                .into_iter()
+                .map(|(shade, color)| format!("      \"{}\": \"{}\"", shade, color))
+                .collect();
+            members.push(format!(
+                "    \"accent_shades\": {{\n{}\n    }}",
+                shades.join(",\n")
+            ));
+        }
+    }
+
+    format!(
+        "{{\n  \"name\": {},\n  \"palette\": {{\n{}\n  }}\n}}\n",
+        quote(&theme.name),
+        members.join(",\n")
+    )
+}
+
+/// Check if some content has the layout of exported themes (a `palette` object).
+pub(crate) fn is_layout(content: &str) -> bool {
+    Json::parse(content).is_ok_and(|json| matches!(json.get("palette"), Some(Json::Object(_))))
+}
+
+/// Parse an exported theme, returns its name, palette roles and accent shades.
+pub(crate) fn parse(content: &str) -> Result<Layout, Error> {
+    let json = Json::parse(content)?;
+    let palette = json
+        .get("palette")
+        .ok_or_else(|| Error::Parse("missing palette".to_string()))?;
+
+    let name = json.get("name").and_then(Json::as_str).map(str::to_string);
+    let roles = super::layout_colors(&ROLES, palette.strings())?;
+    let shades = match palette.get("accent_shades") {
+        Some(shades) => super::layout_colors(&SHADES, shades.strings())?,
+        None => Vec::new(),
+    };
+
+    Ok((name, roles, shades))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(value: &str) -> Json {
+        Json::String(value.to_string())
+    }
+
+    #[test]
+    fn values() {
+        let json = Json::parse(r#"{"a": [1, -2.5e1, true, false, null], "b": "c"}"#).unwrap();
+        let expected = Json::Object(vec![
+            (
+                "a".to_string(),
+                Json::Array(vec![
+                    Json::Number(1.0),
+                    Json::Number(-25.0),
+                    Json::Bool(true),
+                    Json::Bool(false),
+                    Json::Null,
+                ]),
+            ),
+            ("b".to_string(), string("c")),
+        ]);
+
+        assert_eq!(json, expected);
+        assert_eq!(json.get("b").and_then(Json::as_str), Some("c"));
+        assert_eq!(json.get("missing"), None);
+    }
+
+    #[test]
+    fn comments() {
+        let content =
+            "// Theme\n{\n  /* block\n comment */ \"a\": /* inline */ \"b\" // end\n}\n// trailing";
+        let json = Json::parse(content).unwrap();
+        assert_eq!(json.get("a"), Some(&string("b")));
+
+        // Slashes in strings aren't comments
+        let json = Json::parse(r#"{"url": "https://example.com/*"}"#).unwrap();
+        assert_eq!(json.get("url"), Some(&string("https://example.com/*")));
+
+        assert!(Json::parse("{\"a\": 1 /* unterminated").is_err());
+    }
+
+    #[test]
+    fn trailing_commas() {
+        let json = Json::parse("{\"a\": [1, 2,], \"b\": {},}").unwrap();
+        assert_eq!(
+            json.get("a"),
+            Some(&Json::Array(vec![Json::Number(1.0), Json::Number(2.0)]))
+        );
+
+        assert!(Json::parse("[1,,]").is_err());
+        assert!(Json::parse("{,}").is_err());
+    }
+
+    #[test]
+    fn escapes() {
+        let json = Json::parse(r#""\"\\\/\n\té🎨""#).unwrap();
+        assert_eq!(json, string("\"\\/\n\té🎨"));
+
+        assert!(Json::parse(r#""\x""#).is_err());
+        assert!(Json::parse(r#""\u12""#).is_err());
+    }
+
+    #[test]
+    fn error_position() {
+        let error = Json::parse("{\n  \"a\": tru\n}").unwrap_err();
+        assert!(matches!(error, Error::Parse(message) if message.ends_with("line 2, column 11")));
+
+        assert!(Json::parse("{} {}").is_err());
+        assert!(Json::parse("").is_err());
+    }
+
+    #[test]
+    fn quoting() {
+        let value = "name \"with\" \\ and\ncontrol \u{1}";
+        let content = format!("{} # comment", quote(value));
+        let (unquoted, rest) = unquote(&content).unwrap();
+
+        assert_eq!(unquoted, value);
+        assert_eq!(rest, " # comment");
+    }
+}
@@ -0,0 +1,325 @@
+//! KDE color schemes (`.colors` files, also used by `kdeglobals`)
+//!
+//! Invalid values are skipped, like KConfig does. Roles KDE has no color for are exported to a
+//! `[SystemTheme]` group, which KDE ignores and the import reads first.
+use super::{RoleKeys, Roles};
+use crate::{error::Error, ini::Ini, Theme, ThemeColor};
+
+/// Amount of window foreground mixed into the window background for frames (as Breeze does)
+const FRAME_MIX: f32 = 0.25;
+/// Amount of foreground mixed into the backgrounds for alternate rows (exported only)
+const ALTERNATE_MIX: f32 = 0.03;
+
+/// Palette roles and their `Group/Key` entries, content colors fall back to the window ones
+const ROLE_KEYS: &RoleKeys = &[
+    (
+        "background",
+        &[
+            "Colors:Window/BackgroundNormal",
+            "Colors:View/BackgroundNormal",
+        ],
+    ),
+    (
+        "foreground",
+        &[
+            "Colors:View/ForegroundNormal",
+            "Colors:Window/ForegroundNormal",
+        ],
+    ),
+    ("view", &["Colors:View/BackgroundNormal"]),
+    (
+        "sidebar",
+        &["SystemTheme/Sidebar", "Colors:Window/BackgroundNormal"],
+    ),
+    (
+        "headerbar",
+        &[
+            "Colors:Header/BackgroundNormal",
+            "Colors:Window/BackgroundNormal",
+        ],
+    ),
+    (
+        "card",
+        &["SystemTheme/Card", "Colors:View/BackgroundNormal"],
+    ),
+    ("border", &["SystemTheme/Border"]),
+    (
+        "muted_foreground",
+        &[
+            "Colors:View/ForegroundInactive",
+            "Colors:Window/ForegroundInactive",
+        ],
+    ),
+    ("disabled_foreground", &["SystemTheme/DisabledForeground"]),
+    (
+        "accent",
+        &["General/AccentColor", "Colors:Selection/BackgroundNormal"],
+    ),
+    (
+        "accent_foreground",
+        &[
+            "SystemTheme/AccentForeground",
+            "Colors:Selection/ForegroundNormal",
+        ],
+    ),
+    ("selection", &["Colors:Selection/BackgroundNormal"]),
+    (
+        "selection_foreground",
+        &["Colors:Selection/ForegroundNormal"],
+    ),
+    (
+        "focus_ring",
+        &[
+            "Colors:View/DecorationFocus",
+            "Colors:Window/DecorationFocus",
+        ],
+    ),
+    (
+        "link",
+        &["Colors:View/ForegroundLink", "Colors:Window/ForegroundLink"],
+    ),
+    (
+        "visited_link",
+        &[
+            "Colors:View/ForegroundVisited",
+            "Colors:Window/ForegroundVisited",
+        ],
+    ),
+    ("tooltip", &["Colors:Tooltip/BackgroundNormal"]),
+    ("tooltip_foreground", &["Colors:Tooltip/ForegroundNormal"]),
+    (
+        "success",
+        &[
+            "Colors:View/ForegroundPositive",
+            "Colors:Window/ForegroundPositive",
+        ],
+    ),
+    (
+        "warning",
+        &[
+            "Colors:View/ForegroundNeutral",
+            "Colors:Window/ForegroundNeutral",
+        ],
+    ),
+    (
+        "danger",
+        &[
+            "Colors:View/ForegroundNegative",
+            "Colors:Window/ForegroundNegative",
+        ],
+    ),
+];
+
+/// Parse a KDE color value (`r,g,b`, `r,g,b,a` or `#rrggbb`)
+pub(crate) fn parse_color(value: &str) -> Option<ThemeColor> {
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+
+        let rgb = u32::from_str_radix(hex, 16).ok()?;
+        return Some(ThemeColor::from_rgb8(
+            (rgb >> 16) as u8,
+            (rgb >> 8) as u8,
+            rgb as u8,
+        ));
+    }
+
+    // Alpha is ignored, theme colors are opaque
+    let mut components = value.split(',').map(|component| component.trim().parse());
+    match (components.next(), components.next(), components.next()) {
+        (Some(Ok(red)), Some(Ok(green)), Some(Ok(blue))) => {
+            Some(ThemeColor::from_rgb8(red, green, blue))
+        }
+        _ => None,
+    }
+}
+
+/// Get a color from a `Group/Key` entry
+fn color(config: &Ini, entry: &str) -> Option<ThemeColor> {
+    let (group, key) = entry.rsplit_once('/')?;
+    config.get(group, key).and_then(parse_color)
+}
+
+/// Get the palette roles defined by a color scheme.
+pub(crate) fn palette_roles(config: &Ini) -> Roles {
+    // Lookups never fail, invalid values are skipped
+    let mut roles =
+        super::map_roles(ROLE_KEYS, |entry| Ok(color(config, entry))).unwrap_or_default();
+
+    // Frames are drawn with the window colors, color schemes have no color for them
+    if roles.iter().any(|(role, _)| *role == "border") {
+        return roles;
+    }
+
+    let frame = color(config, "Colors:Window/BackgroundNormal")
+        .zip(color(config, "Colors:Window/ForegroundNormal"))
+        .map(|(background, foreground)| background.mix(foreground, FRAME_MIX));
+    if let Some(frame) = frame {
+        roles.push(("border", frame));
+    }
+
+    roles
+}
+
+/// Parse a color scheme, returns its name and palette roles.
+pub(crate) fn parse(content: &str) -> Result<(Option<String>, Roles), Error> {
+    let config = Ini::parse(content);
+    let name = config.get("General", "Name").map(str::to_string);
+
+    Ok((name, palette_roles(&config)))
+}
+
+/// Format a color as a KDE color value (`r,g,b`)
+fn format_color(color: ThemeColor) -> String {
+    let [red, green, blue, _] = color.to_rgba8();
+    format!("{},{},{}", red, green, blue)
+}
+
+/// Export a theme.
+///
+/// Color values have no alpha, translucent colors are flattened on the background.
+pub(crate) fn export(theme: &Theme) -> String {
+    let palette = &theme.palette;
+    let color = |color: ThemeColor| format_color(color.composite_over(palette.background));
+
+    // Color groups with their background and foreground, the other colors are shared
+    let groups = [
+        ("Window", palette.background, palette.foreground),
+        ("View", palette.view, palette.foreground),
+        ("Button", palette.card, palette.foreground),
+        ("Header", palette.headerbar, palette.foreground),
+        ("Selection", palette.selection, palette.selection_foreground),
+        ("Tooltip", palette.tooltip, palette.tooltip_foreground),
+    ];
+
+    let mut content = format!(
+        "[General]\nName={}\nAccentColor={}\n",
+        theme.name.replace(['\r', '\n'], " "),
+        color(palette.accent)
+    );
+
+    for (group, background, foreground) in groups {
+        let entries = [
+            ("BackgroundNormal", background),
+            (
+                "BackgroundAlternate",
+                background.mix(foreground, ALTERNATE_MIX),
+            ),
+            ("DecorationFocus", palette.focus_ring),
+            ("DecorationHover", palette.accent),
+            ("ForegroundActive", palette.accent),
+            ("ForegroundInactive", palette.muted_foreground),
+            ("ForegroundLink", palette.link),
+            ("ForegroundNegative", palette.danger),
+            ("ForegroundNeutral", palette.warning),
+            ("ForegroundNormal", foreground),
+            ("ForegroundPositive", palette.success),
+            ("ForegroundVisited", palette.visited_link),
+        ];
+
+        content.push_str(&format!("\n[Colors:{}]\n", group));
+        for (key, value) in entries {
+            content.push_str(&format!("{}={}\n", key, color(value)));
+        }
+    }
+
+    // Title bar colors (KWin)
+    let title_bar = [
+        ("activeBackground", palette.headerbar),
+        ("activeForeground", palette.foreground),
+        ("inactiveBackground", palette.headerbar),
+        ("inactiveForeground", palette.muted_foreground),
+    ];
+    content.push_str("\n[WM]\n");
+    for (key, value) in title_bar {
+        content.push_str(&format!("{}={}\n", key, color(value)));
+    }
+
+    // Roles without a KDE color, so they're read back as they were
+    let extra = [
+        ("AccentForeground", palette.accent_foreground),
+        ("Border", palette.border),
+        ("Card", palette.card),
+        ("DisabledForeground", palette.disabled_foreground),
+        ("Sidebar", palette.sidebar),
+    ];
+    content.push_str("\n[SystemTheme]\n");
+    for (key, value) in extra {
+        content.push_str(&format!("{}={}\n", key, color(value)));
+    }
+
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{format::tests::role, ThemeFormat, ThemeImport};
+
+    #[test]
+    fn breeze_dark() {
+        let content = include_str!("fixtures/BreezeDark.colors");
+        let import = ThemeImport::parse(content, ThemeFormat::Kde).unwrap();
+
+        assert_eq!(import.theme.name, "Breeze Dark");
+        let expected = [
+            ("background", "#202326"),
+            ("foreground", "#FCFCFC"),
+            ("view", "#141618"),
+            ("sidebar", "#202326"),
+            ("headerbar", "#292C30"),
+            ("card", "#141618"),
+            ("muted_foreground", "#A1A9B1"),
+            ("accent", "#3DAEE9"),
+            ("accent_foreground", "#FCFCFC"),
+            ("selection", "#3DAEE9"),
+            ("selection_foreground", "#FCFCFC"),
+            ("focus_ring", "#3DAEE9"),
+            ("link", "#1D99F3"),
+            ("visited_link", "#9B59B6"),
+            ("tooltip", "#292C30"),
+            ("tooltip_foreground", "#FCFCFC"),
+            ("success", "#27AE60"),
+            ("warning", "#F67400"),
+            ("danger", "#DA4453"),
+        ];
+        for (name, color) in expected {
+            assert_eq!(role(&import, name), color, "{}", name);
+        }
+
+        // Frames mix the window colors
+        let frame =
+            ThemeColor::from_rgb8(32, 35, 38).mix(ThemeColor::from_rgb8(252, 252, 252), 0.25);
+        assert_eq!(role(&import, "border"), frame.to_string());
+        assert_eq!(import.defaulted, ["disabled_foreground"]);
+    }
+
+    #[test]
+    fn accent_color_override() {
+        let content = "[General]\nAccentColor=233,61,174\n\n[Colors:Selection]\nBackgroundNormal=61,174,233\n";
+        let import = ThemeImport::parse(content, ThemeFormat::Kde).unwrap();
+
+        assert_eq!(role(&import, "accent"), "#E93DAE");
+        assert_eq!(role(&import, "selection"), "#3DAEE9");
+    }
+
+    #[test]
+    fn color_values() {
+        assert_eq!(
+            parse_color("61,174,233"),
+            Some(ThemeColor::from_rgb8(61, 174, 233))
+        );
+        assert_eq!(
+            parse_color(" 61, 174, 233, 128"),
+            Some(ThemeColor::from_rgb8(61, 174, 233))
+        );
+        assert_eq!(
+            parse_color("#3daee9"),
+            Some(ThemeColor::from_rgb8(61, 174, 233))
+        );
+        assert_eq!(parse_color("#3dae"), None);
+        assert_eq!(parse_color("61,174"), None);
+        assert_eq!(parse_color("61,174,256"), None);
+    }
+}
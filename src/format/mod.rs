@@ -0,0 +1,399 @@
+//! Theme file formats
+//!
+//! Each format maps its own color names onto the palette roles, missing roles are taken from the
+//! default palette of the matching scheme.
+use std::{fs, path::Path};
+
+use crate::{error::Error, theme::ROLES, Theme, ThemeColor, ThemePalette};
+
+mod base16;
+mod css;
+mod gtk;
+mod json;
+pub(crate) mod kde;
+mod toml;
+mod vscode;
+mod xresources;
+
+/// Name of imported themes without one
+const DEFAULT_NAME: &str = "Custom";
+
+/// Palette roles read from a file, with their color
+type Roles = Vec<(&'static str, ThemeColor)>;
+
+/// Theme name, palette roles and accent shades read from an exported file
+type Layout = (Option<String>, Roles, Roles);
+
+/// Palette roles with the format keys they're read from, in order of preference
+type RoleKeys = [(&'static str, &'static [&'static str])];
+
+/// Theme file format
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum ThemeFormat {
+    /// KDE color scheme (`.colors`)
+    Kde,
+    /// GTK or libadwaita CSS with `@define-color` declarations (`.css`)
+    Gtk,
+    /// base16 or base24 scheme (`.yaml`)
+    Base16,
+    /// VS Code color theme (`.json`, comments and trailing commas are allowed)
+    VsCode,
+    /// JSON, with the layout written by [`ExportFormat::Json`] (`.json`)
+    Json,
+    /// TOML, with the layout written by [`ExportFormat::Toml`] (`.toml`)
+    Toml,
+}
+
+impl ThemeFormat {
+    /// Guess the format of a file from its extension.
+    ///
+    /// JSON files are taken as VS Code themes, [`ThemeImport::load`] also checks their content.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+
+        match extension.as_str() {
+            "colors" => Some(Self::Kde),
+            "css" => Some(Self::Gtk),
+            "yaml" | "yml" => Some(Self::Base16),
+            "json" | "jsonc" => Some(Self::VsCode),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// Theme export format
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum ExportFormat {
+    /// CSS custom properties on `:root` (e.g. `--accent`), with the `color-scheme` property
+    Css,
+    /// SCSS variables (e.g. `$accent`)
+    Scss,
+    /// JSON, with the same layout as the `serde` serialization of [`Theme`], read back by
+    /// [`ThemeFormat::Json`]
+    Json,
+    /// TOML, with the same layout as the `serde` serialization of [`Theme`], read back by
+    /// [`ThemeFormat::Toml`]
+    Toml,
+    /// X resources for terminals (e.g. `*background`) and the palette roles (e.g.
+    /// `systemTheme.accent`)
+    Xresources,
+    /// GTK or libadwaita CSS with `@define-color` declarations, read back by [`ThemeFormat::Gtk`]
+    Gtk,
+    /// KDE color scheme, read back by [`ThemeFormat::Kde`]
+    ///
+    /// KDE has no dedicated colors for some roles (e.g. sidebar, card, border and disabled text),
+    /// they're written to a `[SystemTheme]` group that KDE ignores.
+    Kde,
+}
+
+impl Theme {
+    /// Export the theme to a file format.
+    pub fn export(&self, format: ExportFormat) -> String {
+        match format {
+            ExportFormat::Css => css::export_css(self),
+            ExportFormat::Scss => css::export_scss(self),
+            ExportFormat::Json => json::export(self),
+            ExportFormat::Toml => toml::export(self),
+            ExportFormat::Xresources => xresources::export(self),
+            ExportFormat::Gtk => gtk::export(self),
+            ExportFormat::Kde => kde::export(self),
+        }
+    }
+}
+
+/// Imported theme
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeImport {
+    /// Theme read from the file
+    pub theme: Theme,
+    /// Palette roles the file didn't define (as serialized, e.g. `"visited_link"`)
+    ///
+    /// They're taken from the default palette, or derived from the accent color if the file has
+    /// one (e.g. selection).
+    pub defaulted: Vec<&'static str>,
+}
+
+impl ThemeImport {
+    /// Parse a theme file content.
+    pub fn parse(content: &str, format: ThemeFormat) -> Result<Self, Error> {
+        let (name, mut roles) = match format {
+            ThemeFormat::Kde => kde::parse(content)?,
+            ThemeFormat::Gtk => gtk::parse(content)?,
+            ThemeFormat::Base16 => base16::parse(content)?,
+            ThemeFormat::VsCode => vscode::parse(content)?,
+            // Exported themes are read as they were written, accent shades included
+            ThemeFormat::Json => return Self::from_layout(json::parse(content)?),
+            ThemeFormat::Toml => return Self::from_layout(toml::parse(content)?),
+        };
+
+        // Translucent colors (e.g. selections in VS Code themes) are flattened on the background
+        let background = roles
+            .iter()
+            .find(|(role, _)| *role == "background")
+            .map(|(_, color)| *color);
+        if let Some(background) = background {
+            for (role, color) in &mut roles {
+                if *role != "background" && color.alpha < 1.0 {
+                    *color = color.composite_over(background);
+                }
+            }
+        }
+
+        Self::from_roles(name, roles)
+    }
+
+    /// Load a theme file, guessing its format from the extension.
+    ///
+    /// The file name is used if the theme has no name.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let mut format = ThemeFormat::from_path(path).ok_or(Error::Unsupported)?;
+        let content = fs::read_to_string(path).map_err(Error::from_platform)?;
+
+        // Exported themes share the extension of VS Code themes
+        if format == ThemeFormat::VsCode && json::is_layout(&content) {
+            format = ThemeFormat::Json;
+        }
+
+        let mut import = Self::parse(&content, format)?;
+        if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+            if import.theme.name == DEFAULT_NAME {
+                import.theme.name = stem.to_string();
+            }
+        }
+
+        Ok(import)
+    }
+
+    /// Build the theme from the roles read from a file
+    fn from_roles(name: Option<String>, roles: Roles) -> Result<Self, Error> {
+        if roles.is_empty() {
+            return Err(Error::Parse("no palette color found".to_string()));
+        }
+
+        let defaulted = ROLES
+            .into_iter()
+            .filter(|name| !roles.iter().any(|(role, _)| role == name))
+            .collect();
+
+        Ok(Self {
+            theme: Theme {
+                name: name.unwrap_or_else(|| DEFAULT_NAME.to_string()),
+                palette: ThemePalette::from_roles(&roles),
+            },
+            defaulted,
+        })
+    }
+
+    /// Build the theme from the name, roles and accent shades read from an exported file
+    fn from_layout((name, roles, shades): Layout) -> Result<Self, Error> {
+        let mut import = Self::from_roles(name, roles)?;
+
+        // Missing shades keep the ones computed from the accent
+        for (shade, color) in shades {
+            if let Some(slot) = import.theme.palette.accent_shades.shade_mut(shade) {
+                *slot = color;
+            }
+        }
+
+        Ok(import)
+    }
+}
+
+/// Read the colors of the exported layout (palette roles or accent shades), other names are skipped
+fn layout_colors<'a>(
+    names: &[&'static str],
+    values: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Result<Roles, Error> {
+    let mut colors = Vec::new();
+
+    for (key, value) in values {
+        if let Some(name) = names.iter().find(|name| **name == key) {
+            colors.push((*name, parse_color(key, value)?));
+        }
+    }
+
+    Ok(colors)
+}
+
+/// Read the palette roles with a color lookup, using the first key found for each role
+fn map_roles(
+    keys: &RoleKeys,
+    mut color: impl FnMut(&str) -> Result<Option<ThemeColor>, Error>,
+) -> Result<Roles, Error> {
+    let mut roles = Vec::new();
+
+    for (role, keys) in keys {
+        for key in *keys {
+            if let Some(color) = color(key)? {
+                roles.push((*role, color));
+                break;
+            }
+        }
+    }
+
+    Ok(roles)
+}
+
+/// Parse a color value, the error tells which key it belongs to
+fn parse_color(key: &str, value: &str) -> Result<ThemeColor, Error> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| Error::Parse(format!("invalid color for {}: {}", key, value.trim())))
+}
+
+/// Make a theme name safe to write in a comment (block or line)
+fn comment(name: &str) -> String {
+    name.replace("*/", "* /").replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ThemeContrast, ThemeKind, ThemeScheme};
+
+    /// Built-in themes in every scheme and contrast, with and without an accent color
+    fn themes() -> Vec<Theme> {
+        let kinds = [
+            ThemeKind::Windows,
+            ThemeKind::MacOS,
+            ThemeKind::Gtk,
+            ThemeKind::Qt,
+            ThemeKind::Cosmic,
+            ThemeKind::Pantheon,
+            ThemeKind::Xfce,
+            ThemeKind::Yaru,
+        ];
+        let contrasts = [
+            ThemeContrast::Normal,
+            ThemeContrast::High,
+            ThemeContrast::Reduced,
+        ];
+        let accents = [None, Some(ThemeColor::from_rgb8(0xe6, 0x61, 0x00))];
+
+        let mut themes = Vec::new();
+        for kind in kinds {
+            for scheme in [ThemeScheme::Light, ThemeScheme::Dark] {
+                for contrast in contrasts {
+                    for accent in accents {
+                        themes.push(Theme::new(kind, scheme, contrast, accent));
+                    }
+                }
+            }
+        }
+
+        themes
+    }
+
+    /// Get the palette roles as written in files (8 bits per component)
+    fn roles(theme: &Theme) -> Vec<(&'static str, String)> {
+        theme
+            .palette
+            .roles()
+            .into_iter()
+            .map(|(role, color)| (role, color.to_string()))
+            .collect()
+    }
+
+    /// Get a palette role of an imported theme (as `#RRGGBB`)
+    pub(super) fn role(import: &ThemeImport, name: &str) -> String {
+        let palette = &import.theme.palette;
+        let (_, color) = palette
+            .roles()
+            .into_iter()
+            .find(|(role, _)| *role == name)
+            .unwrap();
+        color.to_string()
+    }
+
+    /// Check that a format reads back every role, returns the import
+    fn round_trip(theme: &Theme, export: ExportFormat, format: ThemeFormat) -> Theme {
+        let content = theme.export(export);
+        let import = ThemeImport::parse(&content, format).unwrap();
+
+        assert!(import.defaulted.is_empty(), "{:?}", import.defaulted);
+        assert_eq!(roles(&import.theme), roles(theme), "{}", theme.name);
+        import.theme
+    }
+
+    #[test]
+    fn json_round_trip() {
+        for theme in themes() {
+            let import = round_trip(&theme, ExportFormat::Json, ThemeFormat::Json);
+            assert_eq!(
+                import.export(ExportFormat::Json),
+                theme.export(ExportFormat::Json)
+            );
+        }
+    }
+
+    #[test]
+    fn toml_round_trip() {
+        for theme in themes() {
+            let import = round_trip(&theme, ExportFormat::Toml, ThemeFormat::Toml);
+            assert_eq!(
+                import.export(ExportFormat::Toml),
+                theme.export(ExportFormat::Toml)
+            );
+        }
+    }
+
+    #[test]
+    fn kde_round_trip() {
+        for theme in themes() {
+            let import = round_trip(&theme, ExportFormat::Kde, ThemeFormat::Kde);
+            assert_eq!(import.name, theme.name);
+        }
+    }
+
+    #[test]
+    fn gtk_round_trip() {
+        for theme in themes() {
+            round_trip(&theme, ExportFormat::Gtk, ThemeFormat::Gtk);
+        }
+    }
+
+    #[test]
+    fn escaped_name_round_trip() {
+        let mut theme = Theme::new(
+            ThemeKind::Gtk,
+            ThemeScheme::Dark,
+            ThemeContrast::Normal,
+            None,
+        );
+        theme.name = "Quoted \"name\" with \\ and # é".to_string();
+
+        for (export, format) in [
+            (ExportFormat::Json, ThemeFormat::Json),
+            (ExportFormat::Toml, ThemeFormat::Toml),
+        ] {
+            let import = ThemeImport::parse(&theme.export(export), format).unwrap();
+            assert_eq!(import.theme.name, theme.name);
+        }
+    }
+
+    #[test]
+    fn load_exported_json() {
+        let theme = Theme::default();
+        let path = std::env::temp_dir().join(format!("system-theme-{}.json", std::process::id()));
+        fs::write(&path, theme.export(ExportFormat::Json)).unwrap();
+
+        let import = ThemeImport::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(roles(&import.unwrap().theme), roles(&theme));
+    }
+
+    #[test]
+    fn json_layout_detection() {
+        let theme = Theme::default();
+        assert!(json::is_layout(&theme.export(ExportFormat::Json)));
+        assert!(!json::is_layout(r#"{"name": "Dark", "colors": {}}"#));
+        assert!(!json::is_layout("not json"));
+    }
+}
@@ -0,0 +1,100 @@
+//! TOML, with the layout of the `serde` serialization
+//!
+//! Only the layout of exported themes is read back (tables of `key = "value"` lines), so TOML is
+//! not fully parsed.
+use super::{
+    json::{quote, unquote},
+    Layout,
+};
+use crate::{
+    error::Error,
+    theme::{ROLES, SHADES},
+    Theme, ThemeColor,
+};
+
+/// Get a string value (basic or literal), followed by an optional comment
+fn parse_value(value: &str) -> Result<String, Error> {
+    let value = value.trim();
+
+    let (string, rest) = if value.starts_with('"') {
+        unquote(value)?
+    } else if let Some(literal) = value.strip_prefix('\'') {
+        let (string, rest) = literal
+            .split_once('\'')
+            .ok_or_else(|| Error::Parse(format!("unterminated string: {}", value)))?;
+        (string.to_string(), rest)
+    } else {
+        return Err(Error::Parse(format!("expected a string: {}", value)));
+    };
+
+    let rest = rest.trim();
+    if !rest.is_empty() && !rest.starts_with('#') {
+        return Err(Error::Parse(format!("unexpected content: {}", rest)));
+    }
+
+    Ok(string)
+}
+
+/// Parse an exported theme, returns its name, palette roles and accent shades.
+pub(crate) fn parse(content: &str) -> Result<Layout, Error> {
+    let mut table = String::new();
+    let mut name = None;
+    let mut roles = Vec::new();
+    let mut shades = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let header = header.split(']').next().unwrap_or_default();
+            table = header
+                .split('.')
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .join(".");
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| Error::Parse(format!("expected a key and a value: {}", line)))?;
+        let key = key.trim().trim_matches('"');
+        let value = parse_value(value)?;
+
+        match table.as_str() {
+            "" if key == "name" => name = Some(value),
+            "palette" => roles.push((key, value)),
+            "palette.accent_shades" => shades.push((key, value)),
+            _ => {}
+        }
+    }
+
+    let colors = |names, values: &[(&str, String)]| {
+        let values = values.iter().map(|(key, value)| (*key, value.as_str()));
+        super::layout_colors(names, values)
+    };
+    Ok((name, colors(&ROLES, &roles)?, colors(&SHADES, &shades)?))
+}
+
+/// Write the `key = value` lines of a table
+fn entries(colors: &[(&str, ThemeColor)]) -> String {
+    colors
+        .iter()
+        .map(|(name, color)| format!("{} = \"{}\"\n", name, color))
+        .collect()
+}
+
+/// Export a theme.
+pub(crate) fn export(theme: &Theme) -> String {
+    let palette = &theme.palette;
+
+    format!(
+        "name = {}\n\n[palette]\n{}\n[palette.accent_shades]\n{}",
+        quote(&theme.name),
+        entries(&palette.roles()),
+        entries(&palette.accent_shades.shades())
+    )
+}
@@ -0,0 +1,166 @@
+//! VS Code color themes (JSON with comments)
+//!
+//! Only the workbench `colors` are read, syntax highlighting (`tokenColors`) is ignored.
+use super::{json::Json, RoleKeys, Roles};
+use crate::error::Error;
+
+/// Palette roles and their workbench color keys
+///
+/// Source: https://code.visualstudio.com/api/references/theme-color
+const ROLE_KEYS: &RoleKeys = &[
+    ("background", &["editor.background"]),
+    ("foreground", &["editor.foreground", "foreground"]),
+    ("view", &["editor.background"]),
+    ("sidebar", &["sideBar.background"]),
+    ("headerbar", &["titleBar.activeBackground"]),
+    (
+        "card",
+        &["editorWidget.background", "notifications.background"],
+    ),
+    (
+        "border",
+        &["editorGroup.border", "panel.border", "contrastBorder"],
+    ),
+    ("muted_foreground", &["descriptionForeground"]),
+    ("disabled_foreground", &["disabledForeground"]),
+    (
+        "accent",
+        &[
+            "button.background",
+            "focusBorder",
+            "activityBarBadge.background",
+        ],
+    ),
+    ("accent_foreground", &["button.foreground"]),
+    (
+        "selection",
+        &[
+            "list.activeSelectionBackground",
+            "editor.selectionBackground",
+        ],
+    ),
+    (
+        "selection_foreground",
+        &[
+            "list.activeSelectionForeground",
+            "editor.selectionForeground",
+        ],
+    ),
+    ("focus_ring", &["focusBorder"]),
+    ("link", &["textLink.foreground"]),
+    // There's no visited link color, the active one is the closest
+    (
+        "visited_link",
+        &["textLink.activeForeground", "textLink.foreground"],
+    ),
+    (
+        "tooltip",
+        &["editorHoverWidget.background", "editorWidget.background"],
+    ),
+    (
+        "tooltip_foreground",
+        &[
+            "editorHoverWidget.foreground",
+            "editorWidget.foreground",
+            "foreground",
+        ],
+    ),
+    (
+        "success",
+        &[
+            "terminal.ansiGreen",
+            "gitDecoration.addedResourceForeground",
+        ],
+    ),
+    (
+        "warning",
+        &["editorWarning.foreground", "terminal.ansiYellow"],
+    ),
+    (
+        "danger",
+        &[
+            "errorForeground",
+            "editorError.foreground",
+            "terminal.ansiRed",
+        ],
+    ),
+];
+
+/// Parse a color theme, returns its name and palette roles.
+pub(crate) fn parse(content: &str) -> Result<(Option<String>, Roles), Error> {
+    let theme = Json::parse(content)?;
+    let colors = theme
+        .get("colors")
+        .ok_or_else(|| Error::Parse("missing colors".to_string()))?;
+
+    // Null values reset a color to the default in VS Code
+    let roles = super::map_roles(ROLE_KEYS, |key| match colors.get(key) {
+        None | Some(Json::Null) => Ok(None),
+        Some(Json::String(value)) => super::parse_color(key, value).map(Some),
+        Some(_) => Err(Error::Parse(format!("invalid color for {}", key))),
+    })?;
+
+    let name = theme.get("name").and_then(Json::as_str).map(str::to_string);
+    Ok((name, roles))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{format::tests::role, ThemeColor, ThemeFormat, ThemeImport};
+
+    #[test]
+    fn dark_modern() {
+        let content = include_str!("fixtures/dark-modern.json");
+        let import = ThemeImport::parse(content, ThemeFormat::VsCode).unwrap();
+
+        assert_eq!(import.theme.name, "Dark Modern");
+        let expected = [
+            ("background", "#1F1F1F"),
+            ("foreground", "#CCCCCC"),
+            ("view", "#1F1F1F"),
+            ("sidebar", "#181818"),
+            ("headerbar", "#181818"),
+            ("card", "#202020"),
+            ("muted_foreground", "#9D9D9D"),
+            ("accent", "#0078D4"),
+            ("accent_foreground", "#FFFFFF"),
+            ("focus_ring", "#0078D4"),
+            ("link", "#4DAAFC"),
+            ("visited_link", "#4DAAFC"),
+            ("tooltip", "#202020"),
+            ("tooltip_foreground", "#CCCCCC"),
+            ("danger", "#F85149"),
+        ];
+        for (name, color) in expected {
+            assert_eq!(role(&import, name), color, "{}", name);
+        }
+
+        // Translucent borders are flattened on the editor background
+        let border = ThemeColor::from_rgba8(0xff, 0xff, 0xff, 0x17)
+            .composite_over(ThemeColor::from_rgb8(0x1f, 0x1f, 0x1f));
+        assert_eq!(role(&import, "border"), border.to_string());
+
+        assert_eq!(
+            import.defaulted,
+            [
+                "disabled_foreground",
+                "selection",
+                "selection_foreground",
+                "success",
+                "warning"
+            ]
+        );
+    }
+
+    #[test]
+    fn null_colors() {
+        // Null resets a color, the next key is used
+        let content = r##"{"colors": {"button.background": null, "focusBorder": "#0078d4"}}"##;
+        let import = ThemeImport::parse(content, ThemeFormat::VsCode).unwrap();
+        assert_eq!(role(&import, "accent"), "#0078D4");
+
+        let content = r#"{"colors": {"focusBorder": 1}}"#;
+        assert!(ThemeImport::parse(content, ThemeFormat::VsCode).is_err());
+        assert!(ThemeImport::parse("{}", ThemeFormat::VsCode).is_err());
+    }
+}
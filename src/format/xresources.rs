@@ -0,0 +1,82 @@
+//! X resources
+//!
+//! Terminals (xterm, urxvt, etc.) read the generic resources, other tools can read the palette
+//! roles (e.g. `${xrdb:systemTheme.accent}` in polybar).
+use crate::{Theme, ThemeColor};
+
+/// Resource name prefix of the palette roles
+const PREFIX: &str = "systemTheme";
+
+/// Convert a role name to a resource name (e.g. `mutedForeground`)
+fn resource_name(role: &str) -> String {
+    let mut words = role.split('_');
+    let mut name = words.next().unwrap_or_default().to_string();
+
+    for word in words {
+        let mut chars = word.chars();
+        name.extend(chars.next().map(|char| char.to_ascii_uppercase()));
+        name.push_str(chars.as_str());
+    }
+
+    name
+}
+
+/// Export a theme.
+///
+/// Resources have no alpha, translucent colors are flattened on the background.
+pub(crate) fn export(theme: &Theme) -> String {
+    let palette = &theme.palette;
+    let color = |color: ThemeColor| color.composite_over(palette.background);
+
+    let generic = [
+        ("background", palette.background),
+        ("foreground", palette.foreground),
+        ("cursorColor", palette.foreground),
+        ("highlightColor", palette.selection),
+        ("highlightTextColor", palette.selection_foreground),
+    ];
+    let generic: String = generic
+        .into_iter()
+        .map(|(name, value)| format!("*{}: {}\n", name, color(value)))
+        .collect();
+    let roles: String = palette
+        .roles()
+        .into_iter()
+        .map(|(role, value)| format!("{}.{}: {}\n", PREFIX, resource_name(role), color(value)))
+        .collect();
+
+    format!("! {}\n{}\n{}", super::comment(&theme.name), generic, roles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ThemeContrast, ThemeKind, ThemeScheme};
+
+    #[test]
+    fn resource_names() {
+        assert_eq!(resource_name("accent"), "accent");
+        assert_eq!(resource_name("muted_foreground"), "mutedForeground");
+        assert_eq!(resource_name("visited_link"), "visitedLink");
+    }
+
+    #[test]
+    fn flattened_export() {
+        let mut theme = Theme::new(
+            ThemeKind::Gtk,
+            ThemeScheme::Light,
+            ThemeContrast::Normal,
+            None,
+        );
+        // Half transparent accent over #F6F5F4
+        theme.palette.selection = ThemeColor::from_rgb8(53, 132, 228).with_alpha(0.5);
+
+        let resources = export(&theme);
+        assert_eq!(
+            resources,
+            include_str!("fixtures/export-adwaita-light.Xresources")
+        );
+        assert!(resources.contains("\n*highlightColor: #96BDEC\n"));
+        assert!(resources.contains("\nsystemTheme.selection: #96BDEC\n"));
+    }
+}
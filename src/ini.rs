@@ -0,0 +1,107 @@
+//! Minimal INI parser for desktop configuration files
+//!
+//! Handles the dialects used by GLib keyfiles, KDE config files and GTK `settings.ini`.
+use std::collections::HashMap;
+
+/// Parsed INI file, grouped by section
+#[derive(Debug, Default, Clone)]
+pub struct Ini {
+    sections: HashMap<String, HashMap<String, String>>,
+}
+
+impl Ini {
+    /// Parse INI content.
+    pub fn parse(content: &str) -> Self {
+        let mut ini = Ini::default();
+        let mut section = String::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+
+            // Skip empty lines and comments
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                section = parse_section(line);
+                continue;
+            }
+
+            if let Some((key, value)) = line.split_once('=') {
+                ini.sections
+                    .entry(section.clone())
+                    .or_default()
+                    .insert(parse_key(key), value.trim().to_string());
+            }
+        }
+
+        ini
+    }
+
+    /// Get the value of a key in a section.
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.sections
+            .get(section)
+            .and_then(|keys| keys.get(key))
+            .map(String::as_str)
+    }
+}
+
+// Files are only loaded to read the desktop configuration
+#[cfg(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "openbsd",
+    target_os = "netbsd"
+))]
+impl Ini {
+    /// Load and parse an INI file.
+    pub fn load(path: impl AsRef<std::path::Path>) -> Option<Self> {
+        std::fs::read_to_string(path)
+            .ok()
+            .map(|content| Self::parse(&content))
+    }
+
+    /// Load and merge INI files, later files override earlier ones.
+    ///
+    /// Missing files are skipped, returns `None` if none could be loaded.
+    pub fn load_all(paths: impl IntoIterator<Item = impl AsRef<std::path::Path>>) -> Option<Self> {
+        paths
+            .into_iter()
+            .filter_map(Self::load)
+            .reduce(|mut ini, other| {
+                ini.merge(other);
+                ini
+            })
+    }
+
+    /// Merge another file on top of this one, overriding existing keys.
+    pub fn merge(&mut self, other: Ini) {
+        for (section, keys) in other.sections {
+            self.sections.entry(section).or_default().extend(keys);
+        }
+    }
+}
+
+/// Parse a section header.
+///
+/// KDE nested groups (`[Colors][Window]`) are joined with `:`, like `[Colors:Window]`.
+fn parse_section(line: &str) -> String {
+    line.split(']')
+        .map(|part| part.trim().trim_start_matches('['))
+        // Ignore KDE markers like `[$i]`
+        .filter(|part| !part.is_empty() && !part.starts_with('$'))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Parse a key, removing KDE markers like `[$e]`.
+fn parse_key(key: &str) -> String {
+    let key = key.trim();
+    match key.find("[$") {
+        Some(index) => key[..index].trim_end().to_string(),
+        None => key.to_string(),
+    }
+}
@@ -2,44 +2,44 @@ use std::sync::Arc;
 
 use crate::{Theme, ThemeColor, ThemePalette};
 
-impl Into<iced::Color> for ThemeColor {
-    fn into(self) -> iced::Color {
+impl From<ThemeColor> for iced::Color {
+    fn from(color: ThemeColor) -> Self {
         iced::Color {
-            r: self.red,
-            g: self.green,
-            b: self.blue,
-            a: 1.0,
+            r: color.red,
+            g: color.green,
+            b: color.blue,
+            a: color.alpha,
         }
     }
 }
 
-impl Into<iced::theme::Palette> for ThemePalette {
-    fn into(self) -> iced::theme::Palette {
+impl From<ThemePalette> for iced::theme::Palette {
+    fn from(palette: ThemePalette) -> Self {
         iced::theme::Palette {
-            background: self.background.into(),
-            text: self.foreground.into(),
-            primary: self.accent.into(),
-            success: self.success.into(),
-            warning: self.warning.into(),
-            danger: self.danger.into(),
+            background: palette.background.into(),
+            text: palette.foreground.into(),
+            primary: palette.accent.into(),
+            success: palette.success.into(),
+            warning: palette.warning.into(),
+            danger: palette.danger.into(),
         }
     }
 }
 
-impl Into<iced::theme::Theme> for ThemePalette {
-    fn into(self) -> iced::theme::Theme {
+impl From<ThemePalette> for iced::theme::Theme {
+    fn from(palette: ThemePalette) -> Self {
         iced::theme::Theme::Custom(Arc::new(iced::theme::Custom::new(
             String::from("SystemTheme"),
-            self.into(),
+            palette.into(),
         )))
     }
 }
 
-impl Into<iced::theme::Theme> for Theme {
-    fn into(self) -> iced::theme::Theme {
+impl From<Theme> for iced::theme::Theme {
+    fn from(theme: Theme) -> Self {
         iced::theme::Theme::Custom(Arc::new(iced::theme::Custom::new(
-            String::from(self.name),
-            self.palette.into(),
+            theme.name,
+            theme.palette.into(),
         )))
     }
 }
@@ -1,2 +1,4 @@
 #[cfg(feature = "iced")]
 mod iced;
+#[cfg(feature = "serde")]
+mod serde;
@@ -0,0 +1,180 @@
+use std::fmt;
+
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{ThemeAccentShades, ThemeColor, ThemePalette};
+
+impl Serialize for ThemeColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+struct ColorVisitor;
+
+impl Visitor<'_> for ColorVisitor {
+    type Value = ThemeColor;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a CSS color string (e.g. #RRGGBB or #RRGGBBAA)")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<ThemeColor, E> {
+        value
+            .parse()
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+}
+
+impl<'de> Deserialize<'de> for ThemeColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(ColorVisitor)
+    }
+}
+
+/// Palette roles, any of them can be missing
+#[derive(Deserialize)]
+struct PaletteRoles {
+    background: Option<ThemeColor>,
+    foreground: Option<ThemeColor>,
+    view: Option<ThemeColor>,
+    sidebar: Option<ThemeColor>,
+    headerbar: Option<ThemeColor>,
+    card: Option<ThemeColor>,
+    border: Option<ThemeColor>,
+    muted_foreground: Option<ThemeColor>,
+    disabled_foreground: Option<ThemeColor>,
+    accent: Option<ThemeColor>,
+    accent_shades: Option<ThemeAccentShades>,
+    accent_foreground: Option<ThemeColor>,
+    selection: Option<ThemeColor>,
+    selection_foreground: Option<ThemeColor>,
+    focus_ring: Option<ThemeColor>,
+    link: Option<ThemeColor>,
+    visited_link: Option<ThemeColor>,
+    tooltip: Option<ThemeColor>,
+    tooltip_foreground: Option<ThemeColor>,
+    success: Option<ThemeColor>,
+    warning: Option<ThemeColor>,
+    danger: Option<ThemeColor>,
+}
+
+impl<'de> Deserialize<'de> for ThemePalette {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let roles = PaletteRoles::deserialize(deserializer)?;
+        let colors = [
+            ("background", roles.background),
+            ("foreground", roles.foreground),
+            ("view", roles.view),
+            ("sidebar", roles.sidebar),
+            ("headerbar", roles.headerbar),
+            ("card", roles.card),
+            ("border", roles.border),
+            ("muted_foreground", roles.muted_foreground),
+            ("disabled_foreground", roles.disabled_foreground),
+            ("accent", roles.accent),
+            ("accent_foreground", roles.accent_foreground),
+            ("selection", roles.selection),
+            ("selection_foreground", roles.selection_foreground),
+            ("focus_ring", roles.focus_ring),
+            ("link", roles.link),
+            ("visited_link", roles.visited_link),
+            ("tooltip", roles.tooltip),
+            ("tooltip_foreground", roles.tooltip_foreground),
+            ("success", roles.success),
+            ("warning", roles.warning),
+            ("danger", roles.danger),
+        ]
+        .into_iter()
+        .filter_map(|(role, color)| color.map(|color| (role, color)))
+        .collect::<Vec<_>>();
+
+        let mut palette = ThemePalette::from_roles(&colors);
+        if let Some(shades) = roles.accent_shades {
+            palette.accent_shades = shades;
+        }
+
+        Ok(palette)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Theme, ThemeContrast, ThemeKind, ThemeScheme};
+
+    #[test]
+    fn theme_round_trip() {
+        let mut theme = Theme::new(
+            ThemeKind::Qt,
+            ThemeScheme::Dark,
+            ThemeContrast::Normal,
+            None,
+        );
+        theme.palette.border = ThemeColor::from_rgba8(255, 255, 255, 38);
+
+        let json = serde_json::to_string(&theme).unwrap();
+        assert!(json.contains(r##""background":"#202326""##), "{json}");
+        assert!(json.contains(r##""border":"#FFFFFF26""##), "{json}");
+
+        // Colors are stored as 8-bit values, so only those are kept
+        let parsed: Theme = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+        assert_eq!(format!("{parsed:?}"), format!("{theme:?}"));
+    }
+
+    #[test]
+    fn missing_roles_use_defaults() {
+        let background = ThemeColor::from_rgb8(30, 30, 30);
+        let accent = ThemeColor::from_rgb8(255, 136, 0);
+        let palette: ThemePalette =
+            serde_json::from_str(r##"{"background": "#1E1E1E", "accent": "#FF8800"}"##).unwrap();
+
+        let mut expected = ThemePalette::default_for_background(background);
+        expected.background = background;
+        expected.set_accent(accent);
+
+        assert_eq!(palette, expected);
+        assert_eq!(
+            palette.accent_shades,
+            ThemeAccentShades::from_accent(accent)
+        );
+        // Dark background gives the dark defaults
+        assert!(palette.foreground.relative_luminance() > 0.5);
+    }
+
+    #[test]
+    fn empty_palette() {
+        let palette: ThemePalette = serde_json::from_str("{}").unwrap();
+        assert_eq!(
+            palette,
+            ThemePalette::default_for_background(ThemeColor::WHITE)
+        );
+    }
+
+    #[test]
+    fn invalid_colors() {
+        for json in [r##""#12345""##, r#""notacolor""#, "16777215", "null"] {
+            assert!(serde_json::from_str::<ThemeColor>(json).is_err(), "{json}");
+        }
+
+        let palette = serde_json::from_str::<ThemePalette>(r#"{"background": "nope"}"#);
+        assert!(palette.is_err());
+    }
+
+    #[test]
+    fn alpha_preserved() {
+        let color = ThemeColor::from_rgba8(61, 174, 233, 128);
+
+        let json = serde_json::to_string(&color).unwrap();
+        assert_eq!(json, r##""#3DAEE980""##);
+        assert_eq!(serde_json::from_str::<ThemeColor>(&json).unwrap(), color);
+
+        // Other CSS colors are accepted too
+        let parsed: ThemeColor = serde_json::from_str(r#""rgba(61, 174, 233, 0.5)""#).unwrap();
+        assert_eq!(parsed.to_string(), "#3DAEE980");
+    }
+}
@@ -3,20 +3,30 @@
 //!
 #![warn(missing_docs, rust_2018_idioms, future_incompatible, keyword_idents)]
 
+mod desktop;
 pub mod error;
+mod format;
+mod ini;
 mod integration;
 mod platform;
 mod theme;
 
 use error::Error;
 
-use async_stream::stream;
 use futures_core::stream::Stream;
 use std::hash::Hash;
 use uuid::Uuid;
 
 #[doc(inline)]
-pub use theme::{Theme, ThemeColor, ThemeContrast, ThemeKind, ThemePalette, ThemeScheme};
+pub use desktop::Desktop;
+#[doc(inline)]
+pub use format::{ExportFormat, ThemeFormat, ThemeImport};
+#[doc(inline)]
+pub use theme::{
+    ContrastTarget, Hsl, Hsv, LinearRgb, Oklab, Oklch, Theme, ThemeAccentShades, ThemeChange,
+    ThemeColor, ThemeContrast, ThemeFont, ThemeFontStyle, ThemeFonts, ThemeKind, ThemeMotion,
+    ThemePalette, ThemeScaling, ThemeScheme, TonalPalette, TonalRoles, TonalScheme,
+};
 
 /// System theme implementation.
 pub struct SystemTheme {
@@ -44,11 +54,27 @@ impl SystemTheme {
         self.platform.theme_kind()
     }
 
+    /// Get the desktop environment.
+    pub fn get_desktop(&self) -> Result<Desktop, Error> {
+        self.platform.desktop()
+    }
+
     /// Get the system theme scheme.
+    ///
+    /// The default scheme is returned if the user has no preference,
+    /// see [`SystemTheme::get_scheme_preference`] to tell them apart.
     pub fn get_scheme(&self) -> Result<ThemeScheme, Error> {
         self.platform.theme_scheme()
     }
 
+    /// Get the user scheme preference.
+    ///
+    /// Returns `None` if the user has no preference (e.g. the "Default" style on GNOME),
+    /// so the application can use its own default scheme.
+    pub fn get_scheme_preference(&self) -> Result<Option<ThemeScheme>, Error> {
+        self.platform.theme_scheme_preference()
+    }
+
     /// Get the system theme contrast level.
     pub fn get_contrast(&self) -> Result<ThemeContrast, Error> {
         self.platform.theme_contrast()
@@ -59,31 +85,129 @@ impl SystemTheme {
         self.platform.theme_accent()
     }
 
+    /// Get the lighter and darker shades of the system accent color.
+    ///
+    /// Shades are read from the system if it provides them, otherwise they are computed from the
+    /// accent color (see [`ThemeAccentShades::from_accent`]).
+    pub fn get_accent_shades(&self) -> Result<ThemeAccentShades, Error> {
+        self.platform.theme_accent_shades()
+    }
+
+    /// Get the system motion preference.
+    ///
+    /// Animations should be reduced (or disabled) if the user asked for it.
+    pub fn get_motion(&self) -> Result<ThemeMotion, Error> {
+        self.platform.theme_motion()
+    }
+
+    /// Get the system fonts.
+    pub fn get_fonts(&self) -> Result<ThemeFonts, Error> {
+        self.platform.theme_fonts()
+    }
+
+    /// Get the system text scale and cursor size.
+    pub fn get_scaling(&self) -> Result<ThemeScaling, Error> {
+        self.platform.theme_scaling()
+    }
+
     /// Get the system theme.
     ///
     /// This is based on the system theme kind, scheme, and contrast level.
     /// A fallback color is used if the platform does not provide it.
     pub fn get_theme(&self) -> Theme {
-        let kind = self.get_kind().unwrap_or_default();
+        self.get_theme_with_default(ThemeScheme::default())
+    }
+
+    /// Get the system theme, using the given scheme if the user has no preference.
+    ///
+    /// See [`SystemTheme::get_theme`] for details.
+    pub fn get_theme_with_default(&self, default_scheme: ThemeScheme) -> Theme {
+        self.platform.theme_settings().into_theme(default_scheme)
+    }
+
+    /// Get the system theme kind without blocking the caller.
+    pub async fn get_kind_async(&self) -> Result<ThemeKind, Error> {
+        self.platform.theme_kind_async().await
+    }
+
+    /// Get the desktop environment without blocking the caller.
+    pub async fn get_desktop_async(&self) -> Result<Desktop, Error> {
+        self.platform.desktop_async().await
+    }
+
+    /// Get the system theme scheme without blocking the caller.
+    pub async fn get_scheme_async(&self) -> Result<ThemeScheme, Error> {
+        self.platform.theme_scheme_async().await
+    }
 
-        let scheme = self.get_scheme().unwrap_or_default();
-        let contrast = self.get_contrast().unwrap_or_default();
+    /// Get the user scheme preference without blocking the caller.
+    ///
+    /// See [`SystemTheme::get_scheme_preference`] for details.
+    pub async fn get_scheme_preference_async(&self) -> Result<Option<ThemeScheme>, Error> {
+        self.platform.theme_scheme_preference_async().await
+    }
+
+    /// Get the system theme contrast level without blocking the caller.
+    pub async fn get_contrast_async(&self) -> Result<ThemeContrast, Error> {
+        self.platform.theme_contrast_async().await
+    }
+
+    /// Get the system theme accent color without blocking the caller.
+    pub async fn get_accent_async(&self) -> Result<ThemeColor, Error> {
+        self.platform.theme_accent_async().await
+    }
+
+    /// Get the shades of the system accent color without blocking the caller.
+    pub async fn get_accent_shades_async(&self) -> Result<ThemeAccentShades, Error> {
+        self.platform.theme_accent_shades_async().await
+    }
+
+    /// Get the system motion preference without blocking the caller.
+    pub async fn get_motion_async(&self) -> Result<ThemeMotion, Error> {
+        self.platform.theme_motion_async().await
+    }
 
-        Theme::new(kind, scheme, contrast, self.get_accent().ok())
+    /// Get the system fonts without blocking the caller.
+    pub async fn get_fonts_async(&self) -> Result<ThemeFonts, Error> {
+        self.platform.theme_fonts_async().await
+    }
+
+    /// Get the system text scale and cursor size without blocking the caller.
+    pub async fn get_scaling_async(&self) -> Result<ThemeScaling, Error> {
+        self.platform.theme_scaling_async().await
+    }
+
+    /// Get the system theme without blocking the caller.
+    ///
+    /// See [`SystemTheme::get_theme`] for details.
+    pub async fn get_theme_async(&self) -> Theme {
+        self.get_theme_with_default_async(ThemeScheme::default())
+            .await
+    }
+
+    /// Get the system theme without blocking the caller, using the given scheme
+    /// if the user has no preference.
+    ///
+    /// See [`SystemTheme::get_theme`] for details.
+    pub async fn get_theme_with_default_async(&self, default_scheme: ThemeScheme) -> Theme {
+        self.platform
+            .theme_settings_async()
+            .await
+            .into_theme(default_scheme)
     }
 
     /// Subscribe to system theme changes.
+    ///
+    /// The returned stream does not depend on a specific async runtime.
     pub fn subscribe(&self) -> impl Stream<Item = ()> {
-        let notify = self.platform.get_notify();
-        stream! {
-            let mut notified = notify.notified();
-            loop {
-                // Wait for notification
-                notified.await;
-                // Create new notified before yielding
-                notified = notify.notified();
-                yield ();
-            }
-        }
+        self.platform.subscribe()
+    }
+
+    /// Subscribe to typed system theme changes.
+    ///
+    /// Each event carries the new value, so there's no need to query it again.
+    /// The returned stream does not depend on a specific async runtime.
+    pub fn subscribe_changes(&self) -> impl Stream<Item = ThemeChange> {
+        self.platform.subscribe_changes()
     }
 }
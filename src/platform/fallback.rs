@@ -1,9 +1,14 @@
-use crate::{error::Error, ThemeColor, ThemeContrast, ThemeKind, ThemeScheme};
-use std::sync::Arc;
+use super::ThemeSettings;
+use crate::{
+    error::Error, Desktop, ThemeAccentShades, ThemeChange, ThemeColor, ThemeContrast, ThemeFonts,
+    ThemeKind, ThemeMotion, ThemeScaling, ThemeScheme,
+};
+use futures_core::stream::Stream;
+use futures_util::StreamExt;
 use std::sync::LazyLock;
-use tokio::sync::Notify;
+use tokio::sync::broadcast::Sender;
 
-static DUMMY_NOTIFY: LazyLock<Arc<Notify>> = LazyLock::new(|| Arc::new(Notify::new()));
+static DUMMY_CHANGES: LazyLock<Sender<ThemeChange>> = LazyLock::new(super::changes_sender);
 
 pub struct Platform {}
 
@@ -16,10 +21,18 @@ impl Platform {
         Err(Error::Unsupported)
     }
 
+    pub fn desktop(&self) -> Result<Desktop, Error> {
+        Err(Error::Unsupported)
+    }
+
     pub fn theme_scheme(&self) -> Result<ThemeScheme, Error> {
         Err(Error::Unsupported)
     }
 
+    pub fn theme_scheme_preference(&self) -> Result<Option<ThemeScheme>, Error> {
+        Err(Error::Unsupported)
+    }
+
     pub fn theme_contrast(&self) -> Result<ThemeContrast, Error> {
         Err(Error::Unsupported)
     }
@@ -28,7 +41,82 @@ impl Platform {
         Err(Error::Unsupported)
     }
 
-    pub fn get_notify(&self) -> Arc<Notify> {
-        (*DUMMY_NOTIFY).clone()
+    pub fn theme_accent_shades(&self) -> Result<ThemeAccentShades, Error> {
+        self.theme_accent().map(ThemeAccentShades::from_accent)
+    }
+
+    pub fn theme_motion(&self) -> Result<ThemeMotion, Error> {
+        Err(Error::Unsupported)
+    }
+
+    pub fn theme_fonts(&self) -> Result<ThemeFonts, Error> {
+        Err(Error::Unsupported)
+    }
+
+    pub fn theme_scaling(&self) -> Result<ThemeScaling, Error> {
+        Err(Error::Unsupported)
+    }
+
+    pub fn theme_settings(&self) -> ThemeSettings {
+        ThemeSettings {
+            kind: self.theme_kind(),
+            scheme: self.theme_scheme_preference(),
+            contrast: self.theme_contrast(),
+            accent: self.theme_accent(),
+            accent_shades: None,
+            theme: None,
+        }
+    }
+
+    pub async fn theme_kind_async(&self) -> Result<ThemeKind, Error> {
+        self.theme_kind()
+    }
+
+    pub async fn desktop_async(&self) -> Result<Desktop, Error> {
+        self.desktop()
+    }
+
+    pub async fn theme_scheme_async(&self) -> Result<ThemeScheme, Error> {
+        self.theme_scheme()
+    }
+
+    pub async fn theme_scheme_preference_async(&self) -> Result<Option<ThemeScheme>, Error> {
+        self.theme_scheme_preference()
+    }
+
+    pub async fn theme_contrast_async(&self) -> Result<ThemeContrast, Error> {
+        self.theme_contrast()
+    }
+
+    pub async fn theme_accent_async(&self) -> Result<ThemeColor, Error> {
+        self.theme_accent()
+    }
+
+    pub async fn theme_accent_shades_async(&self) -> Result<ThemeAccentShades, Error> {
+        self.theme_accent_shades()
+    }
+
+    pub async fn theme_motion_async(&self) -> Result<ThemeMotion, Error> {
+        self.theme_motion()
+    }
+
+    pub async fn theme_fonts_async(&self) -> Result<ThemeFonts, Error> {
+        self.theme_fonts()
+    }
+
+    pub async fn theme_scaling_async(&self) -> Result<ThemeScaling, Error> {
+        self.theme_scaling()
+    }
+
+    pub async fn theme_settings_async(&self) -> ThemeSettings {
+        self.theme_settings()
+    }
+
+    pub fn subscribe(&self) -> impl Stream<Item = ()> {
+        self.subscribe_changes().map(|_| ())
+    }
+
+    pub fn subscribe_changes(&self) -> impl Stream<Item = ThemeChange> {
+        super::changes_stream(&DUMMY_CHANGES)
     }
 }
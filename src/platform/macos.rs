@@ -1,4 +1,6 @@
-use core::{ffi::c_void, ptr};
+use core::{cell::Cell, ffi::c_void, ptr};
+use futures_core::stream::Stream;
+use futures_util::StreamExt;
 use objc2::{
     available, define_class, msg_send,
     rc::Retained,
@@ -7,24 +9,32 @@ use objc2::{
 };
 use objc2_app_kit::{
     NSAppearance, NSAppearanceNameAqua, NSAppearanceNameDarkAqua, NSApplication, NSColor,
-    NSColorSpace, NSSystemColorsDidChangeNotification, NSWorkspace,
-    NSWorkspaceAccessibilityDisplayOptionsDidChangeNotification,
+    NSColorSpace, NSFont, NSFontDescriptorSymbolicTraits, NSSystemColorsDidChangeNotification,
+    NSWorkspace, NSWorkspaceAccessibilityDisplayOptionsDidChangeNotification,
 };
 use objc2_foundation::{
     ns_string, NSArray, NSDictionary, NSKeyValueChangeKey, NSKeyValueObservingOptions,
     NSNotification, NSNotificationCenter, NSObject, NSObjectNSKeyValueObserverRegistration,
     NSString,
 };
-use std::sync::Arc;
-use tokio::sync::Notify;
+use tokio::sync::broadcast::Sender;
 
-use crate::{error::Error, ThemeColor, ThemeContrast, ThemeKind, ThemeScheme};
+use super::ThemeSettings;
+use crate::{
+    error::Error, Desktop, ThemeAccentShades, ThemeChange, ThemeColor, ThemeContrast, ThemeFont,
+    ThemeFontStyle, ThemeFonts, ThemeKind, ThemeMotion, ThemeScaling, ThemeScheme,
+};
 
 struct Ivars {
     application: Retained<NSApplication>,
+    workspace: Retained<NSWorkspace>,
     default_notification: Retained<NSNotificationCenter>,
     workspace_notification: Retained<NSNotificationCenter>,
-    notify: Arc<Notify>,
+    changes: Sender<ThemeChange>,
+    /// Last accent color, as the notification is sent for any system color change
+    accent: Cell<Option<ThemeColor>>,
+    /// Last accessibility options, as the notification doesn't tell which one changed
+    accessibility: Cell<(Option<ThemeContrast>, Option<ThemeMotion>)>,
 }
 
 define_class!(
@@ -47,14 +57,37 @@ define_class!(
             _change: Option<&NSDictionary<NSKeyValueChangeKey, AnyObject>>,
             _context: *mut c_void,
         ) {
-            self.ivars().notify.notify_waiters();
+            if let Ok(scheme) = read_scheme(&self.ivars().application) {
+                let _ = self.ivars().changes.send(ThemeChange::Scheme(Some(scheme)));
+            }
+        }
+
+        #[unsafe(method(accentCallback:))]
+        fn accent_callback(
+            &self, _notification: &NSNotification
+        ) {
+            // Only send if the accent actually changed
+            let accent = read_accent().ok();
+            if accent != self.ivars().accent.replace(accent) {
+                let _ = self.ivars().changes.send(ThemeChange::Accent(accent));
+            }
         }
 
-        #[unsafe(method(notificationCallback:))]
-        fn notification_callback(
+        #[unsafe(method(accessibilityCallback:))]
+        fn accessibility_callback(
             &self, _notification: &NSNotification
         ) {
-            self.ivars().notify.notify_waiters();
+            let contrast = read_contrast(&self.ivars().workspace).ok();
+            let motion = read_motion(&self.ivars().workspace).ok();
+
+            // Only send what actually changed
+            let (last_contrast, last_motion) = self.ivars().accessibility.replace((contrast, motion));
+            if let Some(contrast) = contrast.filter(|contrast| Some(*contrast) != last_contrast) {
+                let _ = self.ivars().changes.send(ThemeChange::Contrast(contrast));
+            }
+            if let Some(motion) = motion.filter(|motion| Some(*motion) != last_motion) {
+                let _ = self.ivars().changes.send(ThemeChange::Motion(motion));
+            }
         }
     }
 );
@@ -63,14 +96,20 @@ impl ThemeObserver {
     pub fn new(
         mtm: MainThreadMarker,
         application: Retained<NSApplication>,
-        workspace: &NSWorkspace,
-        notify: Arc<Notify>,
+        workspace: Retained<NSWorkspace>,
+        changes: Sender<ThemeChange>,
     ) -> Retained<Self> {
         let observer = ThemeObserver::alloc(mtm).set_ivars(Ivars {
             application,
             default_notification: NSNotificationCenter::defaultCenter(),
             workspace_notification: workspace.notificationCenter(),
-            notify,
+            accent: Cell::new(read_accent().ok()),
+            accessibility: Cell::new((
+                read_contrast(&workspace).ok(),
+                read_motion(&workspace).ok(),
+            )),
+            workspace,
+            changes,
         });
         // SAFETY: Object is allocated and ivars are set.
         let observer: Retained<Self> = unsafe { msg_send![super(observer), init] };
@@ -97,13 +136,13 @@ impl ThemeObserver {
                 .default_notification
                 .addObserver_selector_name_object(
                     &observer,
-                    Sel::register(c"notificationCallback:"),
+                    Sel::register(c"accentCallback:"),
                     Some(NSSystemColorsDidChangeNotification),
                     None,
                 );
         }
 
-        // Create theme contrast observer (high / normal)
+        // Create accessibility observer (contrast and motion)
         // SAFETY: We make sure to un-register the observer before it's deallocated.
         unsafe {
             observer
@@ -111,7 +150,7 @@ impl ThemeObserver {
                 .workspace_notification
                 .addObserver_selector_name_object(
                     &observer,
-                    Sel::register(c"notificationCallback:"),
+                    Sel::register(c"accessibilityCallback:"),
                     Some(NSWorkspaceAccessibilityDisplayOptionsDidChangeNotification),
                     None,
                 );
@@ -150,16 +189,111 @@ fn is_appearance_dark(appearance: Retained<NSAppearance>) -> bool {
     }
 }
 
+/// Get the theme scheme from the application appearance
+fn read_scheme(application: &NSApplication) -> Result<ThemeScheme, Error> {
+    // Method used is supported since 10.14
+    if !available!(macos = 10.14) {
+        return Err(Error::Unsupported);
+    }
+
+    if is_appearance_dark(application.effectiveAppearance()) {
+        Ok(ThemeScheme::Dark)
+    } else {
+        Ok(ThemeScheme::Light)
+    }
+}
+
+/// Get the contrast level from the workspace accessibility options
+fn read_contrast(workspace: &NSWorkspace) -> Result<ThemeContrast, Error> {
+    // Method used is supported since 10.10
+    if !available!(macos = 10.10) {
+        return Err(Error::Unsupported);
+    }
+
+    let contrast = if workspace.accessibilityDisplayShouldIncreaseContrast() {
+        ThemeContrast::High
+    } else {
+        ThemeContrast::Normal
+    };
+
+    Ok(contrast)
+}
+
+/// Get the motion preference from the workspace accessibility options
+fn read_motion(workspace: &NSWorkspace) -> Result<ThemeMotion, Error> {
+    // Method used is supported since 10.12
+    if !available!(macos = 10.12) {
+        return Err(Error::Unsupported);
+    }
+
+    let motion = if workspace.accessibilityDisplayShouldReduceMotion() {
+        ThemeMotion::Reduced
+    } else {
+        ThemeMotion::Normal
+    };
+
+    Ok(motion)
+}
+
+/// Convert a font, only regular and bold weights can be told apart from the traits
+fn convert_font(font: &NSFont) -> Option<ThemeFont> {
+    let traits = font.fontDescriptor().symbolicTraits();
+
+    Some(ThemeFont {
+        family: font.familyName()?.to_string(),
+        size: font.pointSize() as f32,
+        weight: if traits.contains(NSFontDescriptorSymbolicTraits::TraitBold) {
+            700
+        } else {
+            400
+        },
+        style: if traits.contains(NSFontDescriptorSymbolicTraits::TraitItalic) {
+            ThemeFontStyle::Italic
+        } else {
+            ThemeFontStyle::Normal
+        },
+    })
+}
+
+/// Get the system fonts (a zero size gets the default one)
+fn read_fonts() -> Result<ThemeFonts, Error> {
+    Ok(ThemeFonts {
+        interface: convert_font(&NSFont::systemFontOfSize(0.0)),
+        document: NSFont::userFontOfSize(0.0).and_then(|font| convert_font(&font)),
+        monospace: NSFont::userFixedPitchFontOfSize(0.0).and_then(|font| convert_font(&font)),
+        titlebar: convert_font(&NSFont::titleBarFontOfSize(0.0)),
+    })
+}
+
+/// Get the system accent color
+fn read_accent() -> Result<ThemeColor, Error> {
+    // Method used is supported since 10.14
+    if !available!(macos = 10.14) {
+        return Err(Error::Unsupported);
+    }
+
+    match NSColor::controlAccentColor().colorUsingColorSpace(&NSColorSpace::genericRGBColorSpace())
+    {
+        Some(color) => Ok(ThemeColor {
+            red: color.redComponent() as f32,
+            green: color.greenComponent() as f32,
+            blue: color.blueComponent() as f32,
+            alpha: color.alphaComponent() as f32,
+        }),
+        None => Err(Error::Unavailable),
+    }
+}
+
 pub struct Platform {
     workspace: Retained<NSWorkspace>,
     application: Retained<NSApplication>,
     _observer: Retained<ThemeObserver>,
-    notify: Arc<Notify>,
+    changes: Sender<ThemeChange>,
 }
 
 impl Platform {
     pub fn new() -> Result<Self, Error> {
-        let notify = Arc::new(Notify::new());
+        let changes = super::changes_sender();
 
         let mtm = match MainThreadMarker::new() {
             Some(mtm) => mtm,
@@ -170,13 +304,14 @@ impl Platform {
         let workspace = NSWorkspace::sharedWorkspace();
 
         // Theme change observer
-        let _observer = ThemeObserver::new(mtm, application.clone(), &workspace, notify.clone());
+        let _observer =
+            ThemeObserver::new(mtm, application.clone(), workspace.clone(), changes.clone());
 
         Ok(Platform {
             workspace,
             application,
             _observer,
-            notify,
+            changes,
         })
     }
 
@@ -184,53 +319,103 @@ impl Platform {
         Ok(ThemeKind::MacOS)
     }
 
+    pub fn desktop(&self) -> Result<Desktop, Error> {
+        Ok(Desktop::MacOS)
+    }
+
     pub fn theme_scheme(&self) -> Result<ThemeScheme, Error> {
-        // Method used is supported since 10.14
-        if !available!(macos = 10.14) {
-            return Err(Error::Unsupported);
-        }
+        read_scheme(&self.application)
+    }
 
-        if is_appearance_dark(self.application.effectiveAppearance()) {
-            Ok(ThemeScheme::Dark)
-        } else {
-            Ok(ThemeScheme::Light)
-        }
+    pub fn theme_scheme_preference(&self) -> Result<Option<ThemeScheme>, Error> {
+        // macOS has no "no preference" option, the appearance is either light or dark
+        self.theme_scheme().map(Some)
     }
 
     pub fn theme_contrast(&self) -> Result<ThemeContrast, Error> {
-        // Method used is supported since 10.10
-        if !available!(macos = 10.10) {
-            return Err(Error::Unsupported);
-        }
+        read_contrast(&self.workspace)
+    }
 
-        let contrast = if self.workspace.accessibilityDisplayShouldIncreaseContrast() {
-            ThemeContrast::High
-        } else {
-            ThemeContrast::Normal
-        };
+    pub fn theme_accent(&self) -> Result<ThemeColor, Error> {
+        read_accent()
+    }
 
-        Ok(contrast)
+    pub fn theme_accent_shades(&self) -> Result<ThemeAccentShades, Error> {
+        self.theme_accent().map(ThemeAccentShades::from_accent)
     }
 
-    pub fn theme_accent(&self) -> Result<ThemeColor, Error> {
-        // Method used is supported since 10.14
-        if !available!(macos = 10.14) {
-            return Err(Error::Unsupported);
-        }
+    pub fn theme_motion(&self) -> Result<ThemeMotion, Error> {
+        read_motion(&self.workspace)
+    }
+
+    pub fn theme_fonts(&self) -> Result<ThemeFonts, Error> {
+        read_fonts()
+    }
+
+    pub fn theme_scaling(&self) -> Result<ThemeScaling, Error> {
+        Err(Error::Unsupported)
+    }
 
-        match NSColor::controlAccentColor()
-            .colorUsingColorSpace(&NSColorSpace::genericRGBColorSpace())
-        {
-            Some(color) => Ok(ThemeColor {
-                red: color.redComponent() as f32,
-                green: color.greenComponent() as f32,
-                blue: color.blueComponent() as f32,
-            }),
-            None => Err(Error::Unavailable),
+    pub fn theme_settings(&self) -> ThemeSettings {
+        ThemeSettings {
+            kind: self.theme_kind(),
+            scheme: self.theme_scheme_preference(),
+            contrast: self.theme_contrast(),
+            accent: self.theme_accent(),
+            accent_shades: None,
+            theme: None,
         }
     }
 
-    pub fn get_notify(&self) -> Arc<Notify> {
-        self.notify.clone()
+    pub async fn theme_kind_async(&self) -> Result<ThemeKind, Error> {
+        self.theme_kind()
+    }
+
+    pub async fn desktop_async(&self) -> Result<Desktop, Error> {
+        self.desktop()
+    }
+
+    pub async fn theme_scheme_async(&self) -> Result<ThemeScheme, Error> {
+        self.theme_scheme()
+    }
+
+    pub async fn theme_scheme_preference_async(&self) -> Result<Option<ThemeScheme>, Error> {
+        self.theme_scheme_preference()
+    }
+
+    pub async fn theme_contrast_async(&self) -> Result<ThemeContrast, Error> {
+        self.theme_contrast()
+    }
+
+    pub async fn theme_accent_async(&self) -> Result<ThemeColor, Error> {
+        self.theme_accent()
+    }
+
+    pub async fn theme_accent_shades_async(&self) -> Result<ThemeAccentShades, Error> {
+        self.theme_accent_shades()
+    }
+
+    pub async fn theme_motion_async(&self) -> Result<ThemeMotion, Error> {
+        self.theme_motion()
+    }
+
+    pub async fn theme_fonts_async(&self) -> Result<ThemeFonts, Error> {
+        self.theme_fonts()
+    }
+
+    pub async fn theme_scaling_async(&self) -> Result<ThemeScaling, Error> {
+        self.theme_scaling()
+    }
+
+    pub async fn theme_settings_async(&self) -> ThemeSettings {
+        self.theme_settings()
+    }
+
+    pub fn subscribe(&self) -> impl Stream<Item = ()> {
+        self.subscribe_changes().map(|_| ())
+    }
+
+    pub fn subscribe_changes(&self) -> impl Stream<Item = ThemeChange> {
+        super::changes_stream(&self.changes)
     }
 }
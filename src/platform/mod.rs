@@ -1,3 +1,7 @@
+use crate::{
+    error::Error, Theme, ThemeAccentShades, ThemeColor, ThemeContrast, ThemeKind, ThemeScheme,
+};
+
 #[cfg(target_os = "macos")]
 mod macos;
 #[cfg(target_os = "macos")]
@@ -45,3 +49,81 @@ mod fallback;
     target_os = "netbsd"
 )))]
 pub use fallback::Platform;
+
+/// Settings needed to build a theme, read in a single pass.
+pub struct ThemeSettings {
+    pub kind: Result<ThemeKind, Error>,
+    /// Scheme preference, `None` if the user has no preference
+    pub scheme: Result<Option<ThemeScheme>, Error>,
+    pub contrast: Result<ThemeContrast, Error>,
+    pub accent: Result<ThemeColor, Error>,
+    /// Accent shades provided by the system, computed from the accent otherwise
+    pub accent_shades: Option<ThemeAccentShades>,
+    /// Theme read from the desktop configuration, used instead of the built-in palettes
+    pub theme: Option<Theme>,
+}
+
+impl ThemeSettings {
+    /// Build the theme, using defaults for unavailable settings.
+    ///
+    /// The default scheme is used if the user has no preference.
+    pub fn into_theme(self, default_scheme: ThemeScheme) -> Theme {
+        if let Some(theme) = self.theme {
+            return theme;
+        }
+
+        let contrast = self.contrast.unwrap_or_default();
+        let mut theme = Theme::new(
+            self.kind.unwrap_or_default(),
+            self.scheme.ok().flatten().unwrap_or(default_scheme),
+            contrast,
+            self.accent.ok(),
+        );
+
+        // System shades only match the accent if it wasn't tuned for the contrast level
+        if let (ThemeContrast::Normal, Some(shades)) = (contrast, self.accent_shades) {
+            theme.palette.accent_shades = shades;
+        }
+
+        theme
+    }
+}
+
+/// Create the channel used to broadcast theme changes.
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "openbsd",
+    target_os = "netbsd"
+)))]
+fn changes_sender() -> tokio::sync::broadcast::Sender<crate::ThemeChange> {
+    // Changes are rare, a small buffer is enough
+    tokio::sync::broadcast::Sender::new(16)
+}
+
+/// Create a stream that yields every change sent through the given channel.
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "openbsd",
+    target_os = "netbsd"
+)))]
+fn changes_stream(
+    sender: &tokio::sync::broadcast::Sender<crate::ThemeChange>,
+) -> impl futures_core::stream::Stream<Item = crate::ThemeChange> {
+    use tokio::sync::broadcast::error::RecvError;
+
+    let mut receiver = sender.subscribe();
+    async_stream::stream! {
+        loop {
+            match receiver.recv().await {
+                Ok(change) => yield change,
+                // Missed changes can't be recovered, keep waiting for new ones
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+}
@@ -1,23 +1,46 @@
-use std::sync::Arc;
-use tokio::sync::Notify;
+use futures_core::stream::Stream;
+use futures_util::StreamExt;
+use std::{
+    ffi::c_void,
+    mem,
+    sync::{Mutex, PoisonError},
+};
+use tokio::sync::broadcast::Sender;
 use windows::{
-    core::HSTRING,
+    core::{Ref, HSTRING},
     Foundation::{Metadata::ApiInformation, TypedEventHandler},
+    Win32::{
+        Graphics::Gdi::LOGFONTW,
+        UI::{
+            HiDpi::GetDpiForSystem,
+            WindowsAndMessaging::{
+                GetSystemMetrics, SystemParametersInfoW, NONCLIENTMETRICSW, SM_CXCURSOR,
+                SPI_GETNONCLIENTMETRICS, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+            },
+        },
+    },
     UI::{
         Color,
-        ViewManagement::{AccessibilitySettings, UIColorType, UISettings},
+        ViewManagement::{AccessibilitySettings, UIColorType, UIElementType, UISettings},
     },
 };
 
-use crate::{error::Error, ThemeColor, ThemeContrast, ThemeKind, ThemeScheme};
+use super::ThemeSettings;
+use crate::{
+    error::Error, Desktop, Theme, ThemeAccentShades, ThemeChange, ThemeColor, ThemeContrast,
+    ThemeFont, ThemeFontStyle, ThemeFonts, ThemeKind, ThemeMotion, ThemeScaling, ThemeScheme,
+};
+
+/// Points per inch, font heights are in pixels at the system DPI
+const POINTS_PER_INCH: f32 = 72.0;
+/// Weight used by GDI when the default one is requested
+const DEFAULT_WEIGHT: u16 = 400;
+/// Reference DPI, system metrics are in pixels at the system DPI
+const DEFAULT_DPI: u32 = 96;
 
 impl From<Color> for ThemeColor {
     fn from(color: Color) -> Self {
-        ThemeColor {
-            red: color.R as f32 / 255.0,
-            green: color.G as f32 / 255.0,
-            blue: color.B as f32 / 255.0,
-        }
+        ThemeColor::from_rgba8(color.R, color.G, color.B, color.A)
     }
 }
 
@@ -45,26 +68,266 @@ fn check_high_contrast_supported() -> Result<bool, Error> {
     .map_err(Error::from_platform)
 }
 
+/// Get the scheme matching a background color
+fn background_scheme(background: Color) -> ThemeScheme {
+    // Simple way for checking if it is dark. Windows returns #000 or #FFF anyways.
+    let color_sum = background.R as u16 + background.G as u16 + background.B as u16;
+    if color_sum < 3 * 128 {
+        ThemeScheme::Dark
+    } else {
+        ThemeScheme::Light
+    }
+}
+
+/// Get the theme scheme from the UI settings
+fn read_scheme(settings: &UISettings) -> Result<ThemeScheme, Error> {
+    // Get the background color reported by windows and check if dark
+    settings
+        .GetColorValue(UIColorType::Background)
+        .map(background_scheme)
+        .map_err(Error::from_platform)
+}
+
+/// Get the theme from the system colors, which must be used as is with a contrast theme
+fn read_forced_theme(settings: &UISettings) -> Result<Theme, Error> {
+    let color = |element| {
+        settings
+            .UIElementColor(element)
+            .map_err(Error::from_platform)
+    };
+
+    let background = color(UIElementType::Window)?;
+    let mut theme = Theme::new(
+        ThemeKind::Windows,
+        background_scheme(background),
+        ThemeContrast::Forced,
+        Some(color(UIElementType::Highlight)?.into()),
+    );
+
+    // Status colors are not part of contrast themes, so the tuned ones are kept
+    let background = ThemeColor::from(background);
+    let foreground = color(UIElementType::WindowText)?.into();
+    let palette = &mut theme.palette;
+    palette.background = background;
+    palette.foreground = foreground;
+    palette.accent_foreground = color(UIElementType::HighlightText)?.into();
+
+    // Contrast themes only have a few colors, every surface uses the window ones
+    for surface in [
+        &mut palette.view,
+        &mut palette.sidebar,
+        &mut palette.headerbar,
+        &mut palette.card,
+        &mut palette.tooltip,
+    ] {
+        *surface = background;
+    }
+    for text in [
+        &mut palette.border,
+        &mut palette.muted_foreground,
+        &mut palette.focus_ring,
+        &mut palette.tooltip_foreground,
+    ] {
+        *text = foreground;
+    }
+    palette.disabled_foreground = color(UIElementType::GrayText)?.into();
+    palette.selection = color(UIElementType::Highlight)?.into();
+    palette.selection_foreground = palette.accent_foreground;
+    palette.link = color(UIElementType::Hotlight)?.into();
+    palette.visited_link = palette.link;
+
+    Ok(theme)
+}
+
+/// Get the accent color from the UI settings
+fn read_accent(settings: &UISettings) -> Result<ThemeColor, Error> {
+    settings
+        .GetColorValue(UIColorType::Accent)
+        .map(|color| color.into())
+        .map_err(Error::from_platform)
+}
+
+/// Get the accent shades generated by the system from the UI settings
+fn read_accent_shades(settings: &UISettings) -> Result<ThemeAccentShades, Error> {
+    let color = |color_type| {
+        settings
+            .GetColorValue(color_type)
+            .map(ThemeColor::from)
+            .map_err(Error::from_platform)
+    };
+
+    Ok(ThemeAccentShades {
+        light1: color(UIColorType::AccentLight1)?,
+        light2: color(UIColorType::AccentLight2)?,
+        light3: color(UIColorType::AccentLight3)?,
+        dark1: color(UIColorType::AccentDark1)?,
+        dark2: color(UIColorType::AccentDark2)?,
+        dark3: color(UIColorType::AccentDark3)?,
+    })
+}
+
+/// Get the motion preference from the UI settings
+fn read_motion(settings: &UISettings) -> Result<ThemeMotion, Error> {
+    settings
+        .AnimationsEnabled()
+        .map(|enabled| {
+            if enabled {
+                ThemeMotion::Normal
+            } else {
+                ThemeMotion::Reduced
+            }
+        })
+        .map_err(Error::from_platform)
+}
+
+/// Get the text scale from the UI settings and the mouse pointer size from the system metrics.
+///
+/// `UISettings::CursorSize` is the text caret size, not the pointer one.
+fn read_scaling(settings: &UISettings) -> Result<ThemeScaling, Error> {
+    let text = settings.TextScaleFactor().map_err(Error::from_platform)?;
+
+    // Pointer size follows the accessibility setting, in pixels at the system DPI
+    let (cursor, dpi) = unsafe { (GetSystemMetrics(SM_CXCURSOR), GetDpiForSystem()) };
+    let cursor_size = u32::try_from(cursor)
+        .ok()
+        .filter(|cursor| *cursor > 0 && dpi > 0)
+        .map(|cursor| cursor * DEFAULT_DPI / dpi);
+
+    Ok(ThemeScaling {
+        text: text as f32,
+        cursor_size,
+    })
+}
+
+/// Convert a GDI logical font
+fn convert_font(font: &LOGFONTW, dpi: u32) -> ThemeFont {
+    let length = font
+        .lfFaceName
+        .iter()
+        .position(|char| *char == 0)
+        .unwrap_or(font.lfFaceName.len());
+
+    // Negative height is the character height, positive is the cell height (close enough)
+    let pixels = font.lfHeight.unsigned_abs() as f32;
+
+    ThemeFont {
+        family: String::from_utf16_lossy(&font.lfFaceName[..length]),
+        size: pixels * POINTS_PER_INCH / dpi as f32,
+        weight: match font.lfWeight {
+            0 => DEFAULT_WEIGHT,
+            weight => weight.clamp(100, 1000) as u16,
+        },
+        style: if font.lfItalic != 0 {
+            ThemeFontStyle::Italic
+        } else {
+            ThemeFontStyle::Normal
+        },
+    }
+}
+
+/// Get the fonts used for the non-client area (title bars, menus, and message boxes)
+fn read_fonts() -> Result<ThemeFonts, Error> {
+    let mut metrics = NONCLIENTMETRICSW {
+        cbSize: mem::size_of::<NONCLIENTMETRICSW>() as u32,
+        ..Default::default()
+    };
+
+    // SAFETY: The buffer is a NONCLIENTMETRICSW with its size set, as required by the action
+    unsafe {
+        SystemParametersInfoW(
+            SPI_GETNONCLIENTMETRICS,
+            metrics.cbSize,
+            Some(&mut metrics as *mut NONCLIENTMETRICSW as *mut c_void),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    }
+    .map_err(Error::from_platform)?;
+
+    // SAFETY: No preconditions, the metrics are scaled for the system DPI
+    let dpi = unsafe { GetDpiForSystem() };
+
+    // Message box font is the one used by the shell for everything else
+    Ok(ThemeFonts {
+        interface: Some(convert_font(&metrics.lfMessageFont, dpi)),
+        titlebar: Some(convert_font(&metrics.lfCaptionFont, dpi)),
+        ..Default::default()
+    })
+}
+
+/// Get the contrast level from the accessibility settings
+fn read_contrast(settings: &AccessibilitySettings) -> Result<ThemeContrast, Error> {
+    // Contrast themes replace the application colors (forced colors)
+    settings
+        .HighContrast()
+        .map(|high_contrast| {
+            if high_contrast {
+                ThemeContrast::Forced
+            } else {
+                ThemeContrast::Normal
+            }
+        })
+        .map_err(Error::from_platform)
+}
+
 pub struct Platform {
     ui_settings: Option<UISettings>,
     a11y_settings: Option<AccessibilitySettings>,
-    notify: Arc<Notify>,
+    changes: Sender<ThemeChange>,
 }
 
 impl Platform {
     pub fn new() -> Result<Self, Error> {
-        let notify = Arc::new(Notify::new());
+        let changes = super::changes_sender();
 
         // Check if GetColorValue is supported
         let ui_settings = if check_color_supported()? {
             let ui_settings = UISettings::new().map_err(Error::from_platform)?;
 
             // Create change watcher (ignore errors, not that important)
-            let notify_cloned = notify.clone();
-            let _ = ui_settings.ColorValuesChanged(&TypedEventHandler::new(move |_, _| {
-                notify_cloned.notify_waiters();
-                Ok(())
-            }));
+            let changes_cloned = changes.clone();
+            let last = Mutex::new((
+                read_scheme(&ui_settings).ok(),
+                read_accent(&ui_settings).ok(),
+            ));
+            let _ = ui_settings.ColorValuesChanged(&TypedEventHandler::new(
+                move |settings: Ref<'_, UISettings>, _| {
+                    let settings = settings.ok()?;
+                    let scheme = read_scheme(settings).ok();
+                    let accent = read_accent(settings).ok();
+
+                    // Event is fired for any color change, only send what actually changed
+                    let mut last = last.lock().unwrap_or_else(PoisonError::into_inner);
+                    if let Some(scheme) = scheme.filter(|scheme| Some(*scheme) != last.0) {
+                        let _ = changes_cloned.send(ThemeChange::Scheme(Some(scheme)));
+                    }
+                    if accent != last.1 {
+                        let _ = changes_cloned.send(ThemeChange::Accent(accent));
+                    }
+                    *last = (scheme, accent);
+
+                    Ok(())
+                },
+            ));
+
+            let changes_cloned = changes.clone();
+            let _ = ui_settings.AnimationsEnabledChanged(&TypedEventHandler::new(
+                move |settings: Ref<'_, UISettings>, _| {
+                    if let Ok(motion) = read_motion(settings.ok()?) {
+                        let _ = changes_cloned.send(ThemeChange::Motion(motion));
+                    }
+                    Ok(())
+                },
+            ));
+
+            let changes_cloned = changes.clone();
+            let _ = ui_settings.TextScaleFactorChanged(&TypedEventHandler::new(
+                move |settings: Ref<'_, UISettings>, _| {
+                    if let Ok(scaling) = read_scaling(settings.ok()?) {
+                        let _ = changes_cloned.send(ThemeChange::Scaling(scaling));
+                    }
+                    Ok(())
+                },
+            ));
 
             Some(ui_settings)
         } else {
@@ -73,7 +336,20 @@ impl Platform {
 
         // Check if HighContrast is supported
         let a11y_settings = if check_high_contrast_supported()? {
-            Some(AccessibilitySettings::new().map_err(Error::from_platform)?)
+            let a11y_settings = AccessibilitySettings::new().map_err(Error::from_platform)?;
+
+            // Create change watcher (ignore errors, not that important)
+            let changes_cloned = changes.clone();
+            let _ = a11y_settings.HighContrastChanged(&TypedEventHandler::new(
+                move |settings: Ref<'_, AccessibilitySettings>, _| {
+                    if let Ok(contrast) = read_contrast(settings.ok()?) {
+                        let _ = changes_cloned.send(ThemeChange::Contrast(contrast));
+                    }
+                    Ok(())
+                },
+            ));
+
+            Some(a11y_settings)
         } else {
             None
         };
@@ -81,7 +357,7 @@ impl Platform {
         Ok(Platform {
             ui_settings,
             a11y_settings,
-            notify,
+            changes,
         })
     }
 
@@ -89,57 +365,130 @@ impl Platform {
         Ok(ThemeKind::Windows)
     }
 
+    pub fn desktop(&self) -> Result<Desktop, Error> {
+        Ok(Desktop::Windows)
+    }
+
     pub fn theme_scheme(&self) -> Result<ThemeScheme, Error> {
-        // Get the background color reported by windows and check if dark
-        let background = self.get_ui_color(UIColorType::Background)?;
+        self.ui_settings
+            .as_ref()
+            .map(read_scheme)
+            .unwrap_or(Err(Error::Unsupported))
+    }
 
-        // Simple way for checking if it is dark. Windows returns #000 or #FFF anyways.
-        let color_sum = background.R as u16 + background.G as u16 + background.B as u16;
-        Ok(if color_sum < 3 * 128 {
-            ThemeScheme::Dark
-        } else {
-            ThemeScheme::Light
-        })
+    pub fn theme_scheme_preference(&self) -> Result<Option<ThemeScheme>, Error> {
+        // Windows has no "no preference" option, apps are either light or dark
+        self.theme_scheme().map(Some)
     }
 
     pub fn theme_contrast(&self) -> Result<ThemeContrast, Error> {
         // Check if high contrast mode is enabled (if supported)
         self.a11y_settings
             .as_ref()
-            .map(|settings| {
-                settings
-                    .HighContrast()
-                    .map(|high_contrast| {
-                        if high_contrast {
-                            ThemeContrast::High
-                        } else {
-                            ThemeContrast::Normal
-                        }
-                    })
-                    .map_err(Error::from_platform)
-            })
+            .map(read_contrast)
             .unwrap_or(Err(Error::Unsupported))
     }
 
     pub fn theme_accent(&self) -> Result<ThemeColor, Error> {
-        // Get main accent color. Ignoring accent shades for now.
-        self.get_ui_color(UIColorType::Accent)
-            .map(|color| color.into())
+        self.ui_settings
+            .as_ref()
+            .map(read_accent)
+            .unwrap_or(Err(Error::Unsupported))
+    }
+
+    pub fn theme_accent_shades(&self) -> Result<ThemeAccentShades, Error> {
+        self.ui_settings
+            .as_ref()
+            .map(read_accent_shades)
+            .unwrap_or(Err(Error::Unsupported))
+    }
+
+    pub fn theme_motion(&self) -> Result<ThemeMotion, Error> {
+        self.ui_settings
+            .as_ref()
+            .map(read_motion)
+            .unwrap_or(Err(Error::Unsupported))
     }
 
-    pub fn get_notify(&self) -> Arc<Notify> {
-        self.notify.clone()
+    pub fn theme_fonts(&self) -> Result<ThemeFonts, Error> {
+        read_fonts()
     }
 
-    fn get_ui_color(&self, color_type: UIColorType) -> Result<Color, Error> {
-        // Get the color reported by windows (if supported)
+    pub fn theme_scaling(&self) -> Result<ThemeScaling, Error> {
         self.ui_settings
             .as_ref()
-            .map(|settings| {
-                settings
-                    .GetColorValue(color_type)
-                    .map_err(Error::from_platform)
-            })
+            .map(read_scaling)
             .unwrap_or(Err(Error::Unsupported))
     }
+
+    pub fn theme_settings(&self) -> ThemeSettings {
+        let contrast = self.theme_contrast();
+
+        // Forced colors must be used instead of the built-in palettes
+        let theme = match (&contrast, &self.ui_settings) {
+            (Ok(ThemeContrast::Forced), Some(ui_settings)) => read_forced_theme(ui_settings).ok(),
+            _ => None,
+        };
+
+        ThemeSettings {
+            kind: self.theme_kind(),
+            scheme: self.theme_scheme_preference(),
+            contrast,
+            accent: self.theme_accent(),
+            accent_shades: self.theme_accent_shades().ok(),
+            theme,
+        }
+    }
+
+    pub async fn theme_kind_async(&self) -> Result<ThemeKind, Error> {
+        self.theme_kind()
+    }
+
+    pub async fn desktop_async(&self) -> Result<Desktop, Error> {
+        self.desktop()
+    }
+
+    pub async fn theme_scheme_async(&self) -> Result<ThemeScheme, Error> {
+        self.theme_scheme()
+    }
+
+    pub async fn theme_scheme_preference_async(&self) -> Result<Option<ThemeScheme>, Error> {
+        self.theme_scheme_preference()
+    }
+
+    pub async fn theme_contrast_async(&self) -> Result<ThemeContrast, Error> {
+        self.theme_contrast()
+    }
+
+    pub async fn theme_accent_async(&self) -> Result<ThemeColor, Error> {
+        self.theme_accent()
+    }
+
+    pub async fn theme_accent_shades_async(&self) -> Result<ThemeAccentShades, Error> {
+        self.theme_accent_shades()
+    }
+
+    pub async fn theme_motion_async(&self) -> Result<ThemeMotion, Error> {
+        self.theme_motion()
+    }
+
+    pub async fn theme_fonts_async(&self) -> Result<ThemeFonts, Error> {
+        self.theme_fonts()
+    }
+
+    pub async fn theme_scaling_async(&self) -> Result<ThemeScaling, Error> {
+        self.theme_scaling()
+    }
+
+    pub async fn theme_settings_async(&self) -> ThemeSettings {
+        self.theme_settings()
+    }
+
+    pub fn subscribe(&self) -> impl Stream<Item = ()> {
+        self.subscribe_changes().map(|_| ())
+    }
+
+    pub fn subscribe_changes(&self) -> impl Stream<Item = ThemeChange> {
+        super::changes_stream(&self.changes)
+    }
 }
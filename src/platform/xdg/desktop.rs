@@ -0,0 +1,305 @@
+//! Desktop environment detection
+use std::env;
+
+use crate::{Desktop, ThemeKind};
+
+const CURRENT_DESKTOP_ENV: &str = "XDG_CURRENT_DESKTOP";
+const DESKTOP_SESSION_ENV: &str = "DESKTOP_SESSION";
+const QT_PLATFORM_THEME_ENV: &str = "QT_QPA_PLATFORMTHEME";
+
+/// Desktop name prefixes, as used by `XDG_CURRENT_DESKTOP` and `DESKTOP_SESSION`
+/// (e.g. "GNOME-Flashback", "plasmawayland", "ubuntu-xorg")
+const DESKTOP_NAMES: &[(&str, Desktop)] = &[
+    ("gnome", Desktop::Gnome),
+    ("ubuntu", Desktop::Gnome),
+    ("kde", Desktop::Kde),
+    ("plasma", Desktop::Kde),
+    ("cosmic", Desktop::Cosmic),
+    ("xfce", Desktop::Xfce),
+    ("x-cinnamon", Desktop::Cinnamon),
+    ("cinnamon", Desktop::Cinnamon),
+    ("mate", Desktop::Mate),
+    ("lxqt", Desktop::Lxqt),
+    ("lxde", Desktop::Lxde),
+    ("budgie", Desktop::Budgie),
+    ("pantheon", Desktop::Pantheon),
+    ("unity", Desktop::Unity),
+    ("deepin", Desktop::Deepin),
+    ("dde", Desktop::Deepin),
+    ("hyprland", Desktop::Hyprland),
+    ("sway", Desktop::Sway),
+];
+
+/// Portal implementations only started by their own desktop
+const PORTAL_IMPLS: &[(&str, Desktop)] = &[
+    ("org.freedesktop.impl.portal.desktop.kde", Desktop::Kde),
+    ("org.freedesktop.impl.portal.desktop.gnome", Desktop::Gnome),
+    (
+        "org.freedesktop.impl.portal.desktop.cosmic",
+        Desktop::Cosmic,
+    ),
+    ("org.freedesktop.impl.portal.desktop.lxqt", Desktop::Lxqt),
+    (
+        "org.freedesktop.impl.portal.desktop.pantheon",
+        Desktop::Pantheon,
+    ),
+    (
+        "org.freedesktop.impl.portal.desktop.hyprland",
+        Desktop::Hyprland,
+    ),
+];
+
+/// GTK theme name prefixes with a matching palette (compared in lowercase)
+const GTK_THEMES: &[(&str, ThemeKind)] = &[
+    ("adwaita", ThemeKind::Gtk),
+    ("yaru", ThemeKind::Yaru),
+    ("greybird", ThemeKind::Xfce),
+    ("elementary", ThemeKind::Pantheon),
+    ("io.elementary.stylesheet", ThemeKind::Pantheon),
+    ("breeze", ThemeKind::Qt),
+];
+
+/// Vendor name used by Ubuntu sessions (e.g. "ubuntu:GNOME")
+const UBUNTU_DESKTOP: &str = "ubuntu";
+
+/// Identify a desktop by its name
+fn from_name(name: &str) -> Option<Desktop> {
+    let name = name.trim().to_ascii_lowercase();
+
+    DESKTOP_NAMES
+        .iter()
+        .find(|(prefix, _)| name.starts_with(prefix))
+        .map(|(_, desktop)| *desktop)
+}
+
+/// Identify the desktop from the environment variables.
+pub fn from_env() -> Option<Desktop> {
+    from_vars(
+        env::var(CURRENT_DESKTOP_ENV).ok().as_deref(),
+        env::var(DESKTOP_SESSION_ENV).ok().as_deref(),
+    )
+}
+
+/// Identify the desktop from the current desktop list and the session name
+fn from_vars(current: Option<&str>, session: Option<&str>) -> Option<Desktop> {
+    // Variable is a colon separated list, most specific first (e.g. "Budgie:GNOME")
+    let current = current.and_then(|desktops| desktops.split(':').find_map(from_name));
+
+    current.or_else(|| {
+        // Older display managers set the session file path
+        from_name(session?.rsplit('/').next()?)
+    })
+}
+
+/// Identify the desktop from the running portal implementations.
+pub fn from_portal_impls(names: &[String]) -> Option<Desktop> {
+    PORTAL_IMPLS
+        .iter()
+        .find(|(name, _)| names.iter().any(|owned| owned == name))
+        .map(|(_, desktop)| *desktop)
+}
+
+/// Get the toolkit used by the desktop applications (`None` for window managers).
+pub fn toolkit(desktop: Desktop) -> Option<ThemeKind> {
+    match desktop {
+        Desktop::Kde | Desktop::Lxqt | Desktop::Deepin => Some(ThemeKind::Qt),
+        Desktop::Cosmic => Some(ThemeKind::Cosmic),
+        Desktop::Pantheon => Some(ThemeKind::Pantheon),
+        Desktop::Xfce => Some(ThemeKind::Xfce),
+        Desktop::Gnome
+        | Desktop::Cinnamon
+        | Desktop::Mate
+        | Desktop::Lxde
+        | Desktop::Budgie
+        | Desktop::Unity => Some(ThemeKind::Gtk),
+        _ => None,
+    }
+}
+
+/// Get the theme kind matching a GTK theme name.
+pub fn gtk_theme_kind(name: &str) -> Option<ThemeKind> {
+    let name = name.trim().to_ascii_lowercase();
+
+    GTK_THEMES
+        .iter()
+        .find(|(prefix, _)| name.starts_with(prefix))
+        .map(|(_, kind)| *kind)
+}
+
+/// Check if running an Ubuntu session, which uses Yaru by default.
+pub fn is_ubuntu() -> bool {
+    let check = |variable| env::var(variable).is_ok_and(|names| is_ubuntu_name(&names));
+
+    check(CURRENT_DESKTOP_ENV) || check(DESKTOP_SESSION_ENV)
+}
+
+/// Check if a desktop list has the Ubuntu vendor name
+fn is_ubuntu_name(names: &str) -> bool {
+    names
+        .split(':')
+        .any(|name| name.eq_ignore_ascii_case(UBUNTU_DESKTOP))
+}
+
+/// Get the toolkit Qt applications are configured to follow.
+pub fn qt_platform_theme() -> Option<ThemeKind> {
+    qt_platform_theme_kind(&env::var(QT_PLATFORM_THEME_ENV).ok()?)
+}
+
+/// Get the toolkit matching a Qt platform theme plugin name
+fn qt_platform_theme_kind(theme: &str) -> Option<ThemeKind> {
+    match theme.to_ascii_lowercase().as_str() {
+        // Qt styled like GTK, so GTK is the main toolkit
+        "gtk2" | "gtk3" | "gnome" => Some(ThemeKind::Gtk),
+        "kde" | "lxqt" | "qt5ct" | "qt6ct" => Some(ThemeKind::Qt),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_desktops() {
+        let cases = [
+            ("GNOME", Some(Desktop::Gnome)),
+            ("ubuntu:GNOME", Some(Desktop::Gnome)),
+            ("GNOME-Flashback:GNOME", Some(Desktop::Gnome)),
+            ("KDE", Some(Desktop::Kde)),
+            ("COSMIC", Some(Desktop::Cosmic)),
+            ("XFCE", Some(Desktop::Xfce)),
+            ("X-Cinnamon", Some(Desktop::Cinnamon)),
+            ("MATE", Some(Desktop::Mate)),
+            ("LXQt", Some(Desktop::Lxqt)),
+            ("LXDE", Some(Desktop::Lxde)),
+            ("Budgie:GNOME", Some(Desktop::Budgie)),
+            ("Pantheon", Some(Desktop::Pantheon)),
+            ("Unity:Unity7:ubuntu", Some(Desktop::Unity)),
+            ("Deepin", Some(Desktop::Deepin)),
+            ("DDE", Some(Desktop::Deepin)),
+            ("Hyprland", Some(Desktop::Hyprland)),
+            ("sway", Some(Desktop::Sway)),
+            // Unknown names are skipped
+            ("i3", None),
+            ("X-Generic:XFCE", Some(Desktop::Xfce)),
+            ("", None),
+        ];
+
+        for (current, desktop) in cases {
+            assert_eq!(from_vars(Some(current), None), desktop, "{current}");
+        }
+    }
+
+    #[test]
+    fn desktop_sessions() {
+        let cases = [
+            ("plasmawayland", Some(Desktop::Kde)),
+            ("plasma", Some(Desktop::Kde)),
+            ("ubuntu-xorg", Some(Desktop::Gnome)),
+            ("/usr/share/xsessions/xfce", Some(Desktop::Xfce)),
+            ("cinnamon", Some(Desktop::Cinnamon)),
+            ("openbox", None),
+        ];
+
+        for (session, desktop) in cases {
+            assert_eq!(from_vars(None, Some(session)), desktop, "{session}");
+        }
+
+        // Session is only used if the current desktop is unknown
+        assert_eq!(
+            from_vars(Some("GNOME"), Some("plasma")),
+            Some(Desktop::Gnome)
+        );
+        assert_eq!(from_vars(Some("i3"), Some("plasma")), Some(Desktop::Kde));
+        assert_eq!(from_vars(None, None), None);
+    }
+
+    #[test]
+    fn ubuntu_sessions() {
+        assert!(is_ubuntu_name("ubuntu:GNOME"));
+        assert!(is_ubuntu_name("Unity:Unity7:ubuntu"));
+        assert!(is_ubuntu_name("Ubuntu"));
+        // Only the exact vendor name, not sessions like "ubuntu-xorg"
+        assert!(!is_ubuntu_name("ubuntu-xorg"));
+        assert!(!is_ubuntu_name("GNOME"));
+        assert!(!is_ubuntu_name(""));
+    }
+
+    #[test]
+    fn qt_platform_themes() {
+        let cases = [
+            ("gtk3", Some(ThemeKind::Gtk)),
+            ("gtk2", Some(ThemeKind::Gtk)),
+            ("gnome", Some(ThemeKind::Gtk)),
+            ("kde", Some(ThemeKind::Qt)),
+            ("KDE", Some(ThemeKind::Qt)),
+            ("lxqt", Some(ThemeKind::Qt)),
+            ("qt5ct", Some(ThemeKind::Qt)),
+            ("qt6ct", Some(ThemeKind::Qt)),
+            ("xdgdesktopportal", None),
+            ("", None),
+        ];
+
+        for (theme, kind) in cases {
+            assert_eq!(qt_platform_theme_kind(theme), kind, "{theme}");
+        }
+    }
+
+    #[test]
+    fn portal_impls() {
+        for (name, desktop) in PORTAL_IMPLS {
+            let names = [":1.42".to_string(), name.to_string()];
+            assert_eq!(from_portal_impls(&names), Some(*desktop), "{name}");
+        }
+
+        // GTK portal is used by many desktops
+        let names = ["org.freedesktop.impl.portal.desktop.gtk".to_string()];
+        assert_eq!(from_portal_impls(&names), None);
+        assert_eq!(from_portal_impls(&[]), None);
+    }
+
+    #[test]
+    fn toolkits() {
+        let cases = [
+            (Desktop::Kde, Some(ThemeKind::Qt)),
+            (Desktop::Lxqt, Some(ThemeKind::Qt)),
+            (Desktop::Deepin, Some(ThemeKind::Qt)),
+            (Desktop::Gnome, Some(ThemeKind::Gtk)),
+            (Desktop::Cinnamon, Some(ThemeKind::Gtk)),
+            (Desktop::Cosmic, Some(ThemeKind::Cosmic)),
+            (Desktop::Pantheon, Some(ThemeKind::Pantheon)),
+            (Desktop::Xfce, Some(ThemeKind::Xfce)),
+            // Window managers have no toolkit
+            (Desktop::Hyprland, None),
+            (Desktop::Sway, None),
+            (Desktop::Unknown, None),
+        ];
+
+        for (desktop, kind) in cases {
+            assert_eq!(toolkit(desktop), kind, "{desktop:?}");
+        }
+    }
+
+    #[test]
+    fn gtk_themes() {
+        let cases = [
+            ("Adwaita", Some(ThemeKind::Gtk)),
+            ("Adwaita-dark", Some(ThemeKind::Gtk)),
+            ("Yaru-blue-dark", Some(ThemeKind::Yaru)),
+            ("Greybird-dark", Some(ThemeKind::Xfce)),
+            ("elementary", Some(ThemeKind::Pantheon)),
+            (
+                "io.elementary.stylesheet.blueberry",
+                Some(ThemeKind::Pantheon),
+            ),
+            ("Breeze-Dark", Some(ThemeKind::Qt)),
+            (" adwaita ", Some(ThemeKind::Gtk)),
+            ("Arc-Dark", None),
+            ("HighContrast", None),
+        ];
+
+        for (name, kind) in cases {
+            assert_eq!(gtk_theme_kind(name), kind, "{name}");
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! Font description parsers
+//!
+//! GNOME settings use Pango descriptions (e.g. "Cantarell Bold 11"),
+//! while KDE settings use the QFont serialization (e.g. "Noto Sans,10,-1,5,400,0,0,0,0,0").
+use crate::{error::Error, ThemeFont, ThemeFontStyle, ThemeFonts};
+
+/// Points per pixel (at 96 DPI)
+const POINTS_PER_PIXEL: f32 = 0.75;
+
+/// Pango style words (compared in lowercase), with the weight or style they set
+const PANGO_WEIGHTS: &[(&str, u16)] = &[
+    ("thin", 100),
+    ("ultra-light", 200),
+    ("ultralight", 200),
+    ("extra-light", 200),
+    ("extralight", 200),
+    ("light", 300),
+    ("semi-light", 350),
+    ("semilight", 350),
+    ("demi-light", 350),
+    ("demilight", 350),
+    ("book", 380),
+    ("regular", 400),
+    ("medium", 500),
+    ("semi-bold", 600),
+    ("semibold", 600),
+    ("demi-bold", 600),
+    ("demibold", 600),
+    ("bold", 700),
+    ("ultra-bold", 800),
+    ("ultrabold", 800),
+    ("extra-bold", 800),
+    ("extrabold", 800),
+    ("heavy", 900),
+    ("black", 900),
+    ("ultra-heavy", 1000),
+    ("ultraheavy", 1000),
+];
+const PANGO_STYLES: &[(&str, ThemeFontStyle)] = &[
+    ("roman", ThemeFontStyle::Normal),
+    ("italic", ThemeFontStyle::Italic),
+    ("oblique", ThemeFontStyle::Oblique),
+];
+/// Pango words that don't change weight or style (stretch and variant)
+const PANGO_IGNORED: &[&str] = &[
+    "normal",
+    "ultra-condensed",
+    "extra-condensed",
+    "condensed",
+    "semi-condensed",
+    "semi-expanded",
+    "expanded",
+    "extra-expanded",
+    "ultra-expanded",
+    "small-caps",
+    "all-small-caps",
+    "petite-caps",
+    "all-petite-caps",
+    "unicase",
+    "title-caps",
+];
+
+/// QFont field count since Qt 6, which uses the OpenType weight scale
+const QT6_FIELDS: usize = 16;
+/// Highest Qt 5 weight, larger ones are already on the OpenType scale
+const QT5_MAX_WEIGHT: u16 = 99;
+/// Qt 5 weights (0 - 99) and their OpenType equivalents
+const QT5_WEIGHTS: &[(u16, u16)] = &[
+    (0, 100),
+    (12, 200),
+    (25, 300),
+    (50, 400),
+    (57, 500),
+    (63, 600),
+    (75, 700),
+    (81, 800),
+    (87, 900),
+];
+
+/// Split the last word of a description (words are separated by spaces or commas)
+fn split_last_word(text: &str) -> Option<(&str, &str)> {
+    let is_separator = |char: char| char.is_ascii_whitespace() || char == ',';
+
+    let text = text.trim_end_matches(is_separator);
+    let index = text.rfind(is_separator)?;
+    Some((&text[..index], &text[index + 1..]))
+}
+
+/// Parse a Pango font description (`[FAMILY-LIST] [STYLE-OPTIONS] SIZE`).
+///
+/// Descriptions without a size are rejected, as the actual size can't be known.
+pub fn parse_pango(description: &str) -> Option<ThemeFont> {
+    let (mut rest, size) = split_last_word(description.trim())?;
+
+    // Size can be in pixels (e.g. "16px")
+    let size = match size.strip_suffix("px") {
+        Some(pixels) => pixels.parse::<f32>().ok()? * POINTS_PER_PIXEL,
+        None => size.parse().ok()?,
+    };
+
+    let mut font = ThemeFont::new("", size);
+
+    // Style options are read backwards, until a word that isn't one (the family)
+    while let Some((before, word)) = split_last_word(rest) {
+        let word = word.to_ascii_lowercase();
+
+        if let Some((_, weight)) = PANGO_WEIGHTS.iter().find(|(name, _)| *name == word) {
+            font.weight = *weight;
+        } else if let Some((_, style)) = PANGO_STYLES.iter().find(|(name, _)| *name == word) {
+            font.style = *style;
+        } else if !PANGO_IGNORED.contains(&word.as_str()) {
+            break;
+        }
+
+        rest = before;
+    }
+
+    // Only the first family of the list is used
+    let family = rest.split(',').next()?.trim();
+    if family.is_empty() {
+        return None;
+    }
+
+    font.family = family.to_string();
+    Some(font)
+}
+
+/// Parse a QFont serialization (`family,pointSize,pixelSize,styleHint,weight,style,...`).
+pub fn parse_qfont(value: &str) -> Option<ThemeFont> {
+    let fields: Vec<&str> = value.split(',').map(str::trim).collect();
+
+    let family = fields.first().filter(|family| !family.is_empty())?;
+    let point_size: f32 = fields.get(1)?.parse().ok()?;
+    let pixel_size: f32 = fields
+        .get(2)
+        .and_then(|size| size.parse().ok())
+        .unwrap_or(-1.0);
+
+    // Unset size is -1, one of them is always set
+    let size = if point_size > 0.0 {
+        point_size
+    } else if pixel_size > 0.0 {
+        pixel_size * POINTS_PER_PIXEL
+    } else {
+        return None;
+    };
+
+    let mut font = ThemeFont::new(*family, size);
+
+    // Qt 6 weights also appear with the Qt 5 field count, so the weight itself tells the scale
+    if let Some(weight) = fields.get(4).and_then(|weight| weight.parse().ok()) {
+        font.weight = if weight > QT5_MAX_WEIGHT || fields.len() >= QT6_FIELDS {
+            weight
+        } else {
+            convert_qt5_weight(weight)
+        };
+    }
+
+    font.style = match fields.get(5).copied() {
+        Some("1") => ThemeFontStyle::Italic,
+        Some("2") => ThemeFontStyle::Oblique,
+        _ => ThemeFontStyle::Normal,
+    };
+
+    Some(font)
+}
+
+/// Convert a Qt 5 weight to the OpenType scale (using the closest known weight)
+fn convert_qt5_weight(weight: u16) -> u16 {
+    QT5_WEIGHTS
+        .iter()
+        .min_by_key(|(qt5, _)| qt5.abs_diff(weight))
+        .map(|(_, weight)| *weight)
+        .unwrap_or(weight)
+}
+
+/// Check that at least one font is configured
+pub fn check_fonts(fonts: ThemeFonts) -> Result<ThemeFonts, Error> {
+    if fonts == ThemeFonts::default() {
+        Err(Error::Unavailable)
+    } else {
+        Ok(fonts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn font(family: &str, size: f32, weight: u16, style: ThemeFontStyle) -> ThemeFont {
+        ThemeFont {
+            weight,
+            style,
+            ..ThemeFont::new(family, size)
+        }
+    }
+
+    #[test]
+    fn pango_descriptions() {
+        let descriptions = [
+            (
+                "Cantarell Bold 11",
+                font("Cantarell", 11.0, 700, ThemeFontStyle::Normal),
+            ),
+            (
+                "Cantarell 11",
+                font("Cantarell", 11.0, 400, ThemeFontStyle::Normal),
+            ),
+            (
+                "Noto Sans Semi-Bold Italic 10.5",
+                font("Noto Sans", 10.5, 600, ThemeFontStyle::Italic),
+            ),
+            (
+                "DejaVu Sans Mono Condensed Oblique 12",
+                font("DejaVu Sans Mono", 12.0, 400, ThemeFontStyle::Oblique),
+            ),
+            (
+                "Inter, Cantarell, Light 9",
+                font("Inter", 9.0, 300, ThemeFontStyle::Normal),
+            ),
+            (
+                "Monospace 16px",
+                font("Monospace", 12.0, 400, ThemeFontStyle::Normal),
+            ),
+        ];
+
+        for (description, expected) in descriptions {
+            assert_eq!(parse_pango(description), Some(expected), "{}", description);
+        }
+    }
+
+    #[test]
+    fn invalid_pango_descriptions() {
+        for description in ["Cantarell", "Cantarell Bold", "", "11"] {
+            assert_eq!(parse_pango(description), None, "{}", description);
+        }
+    }
+
+    #[test]
+    fn qfont_serializations() {
+        let values = [
+            // Qt 5
+            (
+                "Noto Sans,10,-1,5,50,0,0,0,0,0",
+                font("Noto Sans", 10.0, 400, ThemeFontStyle::Normal),
+            ),
+            (
+                "Noto Sans,10,-1,5,75,1,0,0,0,0",
+                font("Noto Sans", 10.0, 700, ThemeFontStyle::Italic),
+            ),
+            (
+                "Hack,9,-1,5,63,0,0,0,0,0,Regular",
+                font("Hack", 9.0, 600, ThemeFontStyle::Normal),
+            ),
+            // Qt 6, short and full forms
+            (
+                "Noto Sans,10,-1,5,400,0,0,0,0,0",
+                font("Noto Sans", 10.0, 400, ThemeFontStyle::Normal),
+            ),
+            (
+                "Noto Sans,10,-1,5,700,0,0,0,0,0,0,0,0,0,0,1",
+                font("Noto Sans", 10.0, 700, ThemeFontStyle::Normal),
+            ),
+            (
+                "Noto Sans,-1,16,5,400,2,0,0,0,0,0,0,0,0,0,1",
+                font("Noto Sans", 12.0, 400, ThemeFontStyle::Oblique),
+            ),
+        ];
+
+        for (value, expected) in values {
+            assert_eq!(parse_qfont(value), Some(expected), "{}", value);
+        }
+    }
+
+    #[test]
+    fn invalid_qfont_serializations() {
+        for value in ["", ",10,-1,5,50", "Noto Sans", "Noto Sans,-1,-1,5,50"] {
+            assert_eq!(parse_qfont(value), None, "{}", value);
+        }
+    }
+}
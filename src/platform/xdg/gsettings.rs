@@ -0,0 +1,364 @@
+//! GSettings reader for the dconf and keyfile backends
+//!
+//! Used when the desktop portal is not available. Only the user database is read.
+use std::{env, fs, path::PathBuf};
+
+use super::{
+    fonts,
+    gvdb::{GValue, Gvdb},
+    provider::Provider,
+};
+use crate::{
+    error::Error, ini::Ini, ThemeColor, ThemeContrast, ThemeFont, ThemeFonts, ThemeMotion,
+    ThemeScheme,
+};
+
+const BACKEND_ENV: &str = "GSETTINGS_BACKEND";
+const KEYFILE_BACKEND: &str = "keyfile";
+
+const DCONF_USER_PATH: &str = "dconf/user";
+const KEYFILE_PATH: &str = "glib-2.0/settings/keyfile";
+
+const INTERFACE_SCHEMA: &str = "org.gnome.desktop.interface";
+const A11Y_INTERFACE_SCHEMA: &str = "org.gnome.desktop.a11y.interface";
+const WM_PREFERENCES_SCHEMA: &str = "org.gnome.desktop.wm.preferences";
+
+const COLOR_SCHEME_KEY: &str = "color-scheme";
+const ACCENT_COLOR_KEY: &str = "accent-color";
+const HIGH_CONTRAST_KEY: &str = "high-contrast";
+const GTK_THEME_KEY: &str = "gtk-theme";
+const ENABLE_ANIMATIONS_KEY: &str = "enable-animations";
+const FONT_NAME_KEY: &str = "font-name";
+const DOCUMENT_FONT_NAME_KEY: &str = "document-font-name";
+const MONOSPACE_FONT_NAME_KEY: &str = "monospace-font-name";
+const TITLEBAR_FONT_KEY: &str = "titlebar-font";
+const TEXT_SCALING_KEY: &str = "text-scaling-factor";
+const CURSOR_SIZE_KEY: &str = "cursor-size";
+
+const PREFER_DARK: &str = "prefer-dark";
+const PREFER_LIGHT: &str = "prefer-light";
+
+/// GVariant text format type annotations (e.g. `uint32 1`)
+const TYPE_ANNOTATIONS: &[&str] = &[
+    "boolean",
+    "byte",
+    "int16",
+    "uint16",
+    "int32",
+    "uint32",
+    "int64",
+    "uint64",
+    "handle",
+    "double",
+    "string",
+    "objectpath",
+    "signature",
+];
+
+/// GNOME accent color
+///
+/// Source: https://gnome.pages.gitlab.gnome.org/libadwaita/doc/main/enum.AccentColor.html
+fn gnome_accent_color(name: &str) -> Option<ThemeColor> {
+    match name {
+        "blue" => Some(ThemeColor::from_rgb8(53, 132, 228)),
+        "teal" => Some(ThemeColor::from_rgb8(33, 144, 164)),
+        "green" => Some(ThemeColor::from_rgb8(58, 148, 74)),
+        "yellow" => Some(ThemeColor::from_rgb8(200, 136, 0)),
+        "orange" => Some(ThemeColor::from_rgb8(237, 91, 0)),
+        "red" => Some(ThemeColor::from_rgb8(230, 45, 66)),
+        "pink" => Some(ThemeColor::from_rgb8(213, 97, 153)),
+        "purple" => Some(ThemeColor::from_rgb8(145, 65, 172)),
+        "slate" => Some(ThemeColor::from_rgb8(111, 131, 150)),
+        _ => None,
+    }
+}
+
+/// Check if using the keyfile backend instead of dconf
+fn is_keyfile_backend() -> bool {
+    env::var(BACKEND_ENV).is_ok_and(|backend| backend == KEYFILE_BACKEND)
+}
+
+/// User settings database
+pub enum GSettings {
+    /// dconf database (default backend)
+    Dconf(Gvdb),
+    /// Keyfile (`GSETTINGS_BACKEND=keyfile`)
+    Keyfile(Ini),
+}
+
+impl GSettings {
+    /// Load the user settings database for the configured backend.
+    pub fn load() -> Option<Self> {
+        let path = Self::path()?;
+
+        if is_keyfile_backend() {
+            Ini::load(path).map(GSettings::Keyfile)
+        } else {
+            fs::read(path)
+                .ok()
+                .and_then(Gvdb::new)
+                .map(GSettings::Dconf)
+        }
+    }
+
+    /// Get the path of the user settings database for the configured backend.
+    pub fn path() -> Option<PathBuf> {
+        let config = super::config_home()?;
+
+        if is_keyfile_backend() {
+            Some(config.join(KEYFILE_PATH))
+        } else {
+            Some(config.join(DCONF_USER_PATH))
+        }
+    }
+
+    /// Get a font key from a schema (stored as a Pango description).
+    fn font(&self, schema: &str, key: &str) -> Option<ThemeFont> {
+        self.get(schema, key)
+            .and_then(|value| value.as_str().and_then(fonts::parse_pango))
+    }
+
+    /// Get a key from a schema (only set keys are available, not schema defaults).
+    pub fn get(&self, schema: &str, key: &str) -> Option<GValue> {
+        // Schema path follows its id (org.gnome.desktop.interface -> org/gnome/desktop/interface)
+        let path = schema.replace('.', "/");
+
+        match self {
+            GSettings::Dconf(database) => database.get(&format!("/{path}/{key}")),
+            GSettings::Keyfile(keyfile) => keyfile.get(&path, key).and_then(parse_text_value),
+        }
+    }
+}
+
+impl Provider for GSettings {
+    fn theme_scheme(&self) -> Result<ThemeScheme, Error> {
+        let scheme = self
+            .get(INTERFACE_SCHEMA, COLOR_SCHEME_KEY)
+            .ok_or(Error::Unavailable)?;
+
+        // "default" means no preference
+        match scheme.as_str() {
+            Some(PREFER_DARK) => Ok(ThemeScheme::Dark),
+            Some(PREFER_LIGHT) => Ok(ThemeScheme::Light),
+            _ => Err(Error::Unavailable),
+        }
+    }
+
+    fn theme_contrast(&self) -> Result<ThemeContrast, Error> {
+        let high_contrast = self
+            .get(A11Y_INTERFACE_SCHEMA, HIGH_CONTRAST_KEY)
+            .and_then(|value| value.as_bool())
+            .ok_or(Error::Unavailable)?;
+
+        if high_contrast {
+            Ok(ThemeContrast::High)
+        } else {
+            Ok(ThemeContrast::Normal)
+        }
+    }
+
+    fn theme_accent(&self) -> Result<ThemeColor, Error> {
+        self.get(INTERFACE_SCHEMA, ACCENT_COLOR_KEY)
+            .and_then(|value| value.as_str().and_then(gnome_accent_color))
+            .ok_or(Error::Unavailable)
+    }
+
+    fn theme_motion(&self) -> Result<ThemeMotion, Error> {
+        let enable_animations = self
+            .get(INTERFACE_SCHEMA, ENABLE_ANIMATIONS_KEY)
+            .and_then(|value| value.as_bool())
+            .ok_or(Error::Unavailable)?;
+
+        if enable_animations {
+            Ok(ThemeMotion::Normal)
+        } else {
+            Ok(ThemeMotion::Reduced)
+        }
+    }
+
+    fn theme_fonts(&self) -> Result<ThemeFonts, Error> {
+        fonts::check_fonts(ThemeFonts {
+            interface: self.font(INTERFACE_SCHEMA, FONT_NAME_KEY),
+            document: self.font(INTERFACE_SCHEMA, DOCUMENT_FONT_NAME_KEY),
+            monospace: self.font(INTERFACE_SCHEMA, MONOSPACE_FONT_NAME_KEY),
+            titlebar: self.font(WM_PREFERENCES_SCHEMA, TITLEBAR_FONT_KEY),
+        })
+    }
+
+    fn text_scale(&self) -> Result<f32, Error> {
+        self.get(INTERFACE_SCHEMA, TEXT_SCALING_KEY)
+            .and_then(|value| value.as_f64())
+            .filter(|factor| *factor > 0.0)
+            .map(|factor| factor as f32)
+            .ok_or(Error::Unavailable)
+    }
+
+    fn cursor_size(&self) -> Result<u32, Error> {
+        self.get(INTERFACE_SCHEMA, CURSOR_SIZE_KEY)
+            .and_then(|value| value.as_i64())
+            .and_then(|size| u32::try_from(size).ok())
+            .filter(|size| *size > 0)
+            .ok_or(Error::Unavailable)
+    }
+
+    fn gtk_theme(&self) -> Result<String, Error> {
+        self.get(INTERFACE_SCHEMA, GTK_THEME_KEY)
+            .and_then(|value| value.as_str().map(str::to_string))
+            .ok_or(Error::Unavailable)
+    }
+}
+
+/// Parse a value in GVariant text format, as stored by the keyfile backend
+fn parse_text_value(text: &str) -> Option<GValue> {
+    let mut text = text.trim();
+
+    // Type annotations are not needed for the supported types
+    if let Some((annotation, value)) = text.split_once(' ') {
+        if TYPE_ANNOTATIONS.contains(&annotation) {
+            text = value.trim();
+        }
+    }
+
+    match text {
+        "true" => return Some(GValue::Bool(true)),
+        "false" => return Some(GValue::Bool(false)),
+        _ => {}
+    }
+
+    // Strings can use single or double quotes
+    for quote in ['\'', '"'] {
+        if let Some(string) = text
+            .strip_prefix(quote)
+            .and_then(|text| text.strip_suffix(quote))
+        {
+            return Some(GValue::String(unescape(string)));
+        }
+    }
+
+    text.parse()
+        .map(GValue::Int)
+        .or_else(|_| text.parse().map(GValue::Double))
+        .ok()
+}
+
+/// Remove escape sequences from a GVariant text string
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            result.push(char);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some(char) => result.push(char),
+            None => {}
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dconf() -> GSettings {
+        let database = Gvdb::new(include_bytes!("fixtures/dconf-user").to_vec()).unwrap();
+        GSettings::Dconf(database)
+    }
+
+    fn keyfile(content: &str) -> GSettings {
+        GSettings::Keyfile(Ini::parse(content))
+    }
+
+    #[test]
+    fn dconf_settings() {
+        let settings = dconf();
+
+        assert_eq!(settings.theme_scheme().unwrap(), ThemeScheme::Dark);
+        assert_eq!(settings.theme_contrast().unwrap(), ThemeContrast::High);
+        assert_eq!(settings.theme_motion().unwrap(), ThemeMotion::Reduced);
+        assert_eq!(settings.text_scale().unwrap(), 1.25);
+        assert_eq!(settings.cursor_size().unwrap(), 32);
+        assert_eq!(settings.gtk_theme().unwrap(), "Adwaita-dark");
+        // Only set keys are available
+        assert!(settings.theme_accent().is_err());
+
+        let fonts = settings.theme_fonts().unwrap();
+        assert_eq!(fonts.interface.unwrap().family, "Cantarell");
+        assert_eq!(fonts.titlebar.unwrap().weight, 700);
+        assert_eq!(fonts.document, None);
+    }
+
+    #[test]
+    fn keyfile_settings() {
+        let settings = keyfile(
+            "[org/gnome/desktop/interface]\n\
+             color-scheme='prefer-light'\n\
+             accent-color='teal'\n\
+             enable-animations=true\n\
+             cursor-size=int32 48\n\
+             text-scaling-factor=1.5\n\
+             [org/gnome/desktop/a11y/interface]\n\
+             high-contrast=false\n",
+        );
+
+        assert_eq!(settings.theme_scheme().unwrap(), ThemeScheme::Light);
+        assert_eq!(settings.theme_contrast().unwrap(), ThemeContrast::Normal);
+        assert_eq!(
+            settings.theme_accent().unwrap(),
+            ThemeColor::from_rgb8(33, 144, 164)
+        );
+        assert_eq!(settings.theme_motion().unwrap(), ThemeMotion::Normal);
+        assert_eq!(settings.cursor_size().unwrap(), 48);
+        assert_eq!(settings.text_scale().unwrap(), 1.5);
+    }
+
+    #[test]
+    fn no_scheme_preference() {
+        let settings = keyfile("[org/gnome/desktop/interface]\ncolor-scheme='default'\n");
+        assert!(settings.theme_scheme().is_err());
+    }
+
+    #[test]
+    fn text_values() {
+        let string = |value: &str| Some(GValue::String(value.to_string()));
+
+        assert_eq!(parse_text_value("true"), Some(GValue::Bool(true)));
+        assert_eq!(parse_text_value("false"), Some(GValue::Bool(false)));
+        assert_eq!(parse_text_value("boolean true"), Some(GValue::Bool(true)));
+        assert_eq!(parse_text_value("uint32 5"), Some(GValue::Int(5)));
+        assert_eq!(parse_text_value("-3"), Some(GValue::Int(-3)));
+        assert_eq!(parse_text_value("1.25"), Some(GValue::Double(1.25)));
+        assert_eq!(parse_text_value("'Adwaita'"), string("Adwaita"));
+        assert_eq!(parse_text_value(" \"Adwaita\" "), string("Adwaita"));
+        assert_eq!(parse_text_value("string 'a b'"), string("a b"));
+        assert_eq!(parse_text_value("''"), string(""));
+    }
+
+    #[test]
+    fn escaped_strings() {
+        let string = |value: &str| Some(GValue::String(value.to_string()));
+
+        assert_eq!(parse_text_value(r"'it\'s'"), string("it's"));
+        assert_eq!(parse_text_value(r#""say \"hi\"""#), string("say \"hi\""));
+        assert_eq!(parse_text_value(r"'a\\b'"), string("a\\b"));
+        assert_eq!(parse_text_value(r"'a\tb\nc'"), string("a\tb\nc"));
+    }
+
+    #[test]
+    fn unsupported_text_values() {
+        // Containers and unknown types are not needed by any setting
+        assert_eq!(parse_text_value("@as []"), None);
+        assert_eq!(parse_text_value("['a', 'b']"), None);
+        assert_eq!(parse_text_value("(1, 2)"), None);
+        assert_eq!(parse_text_value("'unterminated"), None);
+        assert_eq!(parse_text_value(""), None);
+    }
+}
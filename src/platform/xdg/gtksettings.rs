@@ -0,0 +1,202 @@
+//! GTK settings reader (`gtk-3.0/settings.ini` and `gtk-4.0/settings.ini`)
+//!
+//! Used by desktops that don't set a color scheme preference (e.g. Xfce, LXDE, tiling WMs).
+use std::{env, path::PathBuf};
+
+use super::{fonts, provider::Provider};
+use crate::{error::Error, ini::Ini, ThemeContrast, ThemeFonts, ThemeMotion, ThemeScheme};
+
+/// Settings directories, GTK 4 is merged last so it takes precedence
+const SETTINGS_PATHS: &[&str] = &["gtk-3.0/settings.ini", "gtk-4.0/settings.ini"];
+/// Theme override (e.g. `Adwaita:dark`)
+const THEME_ENV: &str = "GTK_THEME";
+
+const SETTINGS_GROUP: &str = "Settings";
+
+const PREFER_DARK_KEY: &str = "gtk-application-prefer-dark-theme";
+const THEME_NAME_KEY: &str = "gtk-theme-name";
+const ENABLE_ANIMATIONS_KEY: &str = "gtk-enable-animations";
+const FONT_NAME_KEY: &str = "gtk-font-name";
+const XFT_DPI_KEY: &str = "gtk-xft-dpi";
+const CURSOR_SIZE_KEY: &str = "gtk-cursor-theme-size";
+const INTERFACE_CONTRAST_KEY: &str = "gtk-interface-contrast";
+
+/// Scale of the Xft DPI setting (stored as 1024 * DPI)
+const XFT_DPI_SCALE: f32 = 1024.0;
+
+/// Theme name markers (compared in lowercase, without separators)
+const DARK_MARKER: &str = "dark";
+const HIGH_CONTRAST_MARKER: &str = "highcontrast";
+/// Inverse high contrast theme is dark
+const INVERSE_MARKER: &str = "inverse";
+
+/// Parse a GTK boolean setting
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" => Some(true),
+        "0" | "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// GTK settings, merged from all configuration directories
+pub struct GtkSettings {
+    config: Ini,
+    theme_override: Option<String>,
+}
+
+impl GtkSettings {
+    /// Load and merge every `settings.ini` file, returns `None` if there's none.
+    pub fn load() -> Option<Self> {
+        let theme_override = env::var(THEME_ENV).ok().filter(|theme| !theme.is_empty());
+        let config = Ini::load_all(Self::paths());
+
+        // Theme override is enough, even without files
+        if config.is_none() && theme_override.is_none() {
+            return None;
+        }
+
+        Some(Self::new(config.unwrap_or_default(), theme_override))
+    }
+
+    /// Use merged settings, with the theme override from the environment.
+    pub fn new(config: Ini, theme_override: Option<String>) -> Self {
+        Self {
+            config,
+            theme_override,
+        }
+    }
+
+    /// Get the paths of every `settings.ini` file, in increasing order of preference.
+    pub fn paths() -> Vec<PathBuf> {
+        super::config_paths(SETTINGS_PATHS)
+    }
+
+    /// Get the theme name (the override takes precedence)
+    fn theme_name(&self) -> Option<&str> {
+        self.theme_override
+            .as_deref()
+            .or_else(|| self.config.get(SETTINGS_GROUP, THEME_NAME_KEY))
+    }
+
+    /// Get the dark variant preference
+    fn prefer_dark(&self) -> Option<bool> {
+        self.config
+            .get(SETTINGS_GROUP, PREFER_DARK_KEY)
+            .and_then(parse_bool)
+    }
+
+    /// Get the theme name in lowercase, without separators
+    fn normalized_theme_name(&self) -> Option<String> {
+        let name = self.theme_name()?;
+
+        Some(
+            name.chars()
+                .filter(char::is_ascii_alphanumeric)
+                .map(|char| char.to_ascii_lowercase())
+                .collect(),
+        )
+    }
+}
+
+impl Provider for GtkSettings {
+    fn theme_scheme(&self) -> Result<ThemeScheme, Error> {
+        // Disabled means the theme default variant is used, not a light preference
+        match self.prefer_dark() {
+            Some(true) => Ok(ThemeScheme::Dark),
+            _ => Err(Error::Unavailable),
+        }
+    }
+
+    fn theme_scheme_hint(&self) -> Result<ThemeScheme, Error> {
+        let prefer_dark = self.prefer_dark();
+        let name = self.normalized_theme_name();
+
+        if prefer_dark == Some(true) {
+            return Ok(ThemeScheme::Dark);
+        }
+
+        // Dark variants are usually separate themes (e.g. "Adwaita-dark", "Arc-Dark")
+        match name {
+            Some(name) if name.contains(DARK_MARKER) || name.contains(INVERSE_MARKER) => {
+                Ok(ThemeScheme::Dark)
+            }
+            Some(_) => Ok(ThemeScheme::Light),
+            None if prefer_dark.is_some() => Ok(ThemeScheme::Light),
+            None => Err(Error::Unavailable),
+        }
+    }
+
+    fn theme_contrast(&self) -> Result<ThemeContrast, Error> {
+        // Contrast preference (GTK 4.20), as a nick or a number
+        let contrast = self.config.get(SETTINGS_GROUP, INTERFACE_CONTRAST_KEY);
+        match contrast.map(str::to_ascii_lowercase).as_deref() {
+            Some("more" | "1") => return Ok(ThemeContrast::High),
+            Some("less" | "2") => return Ok(ThemeContrast::Reduced),
+            _ => {}
+        }
+
+        // Otherwise high contrast themes are used (e.g. "HighContrast", "HighContrastInverse")
+        let name = self.normalized_theme_name().ok_or(Error::Unavailable)?;
+
+        if name.contains(HIGH_CONTRAST_MARKER) {
+            Ok(ThemeContrast::High)
+        } else {
+            Ok(ThemeContrast::Normal)
+        }
+    }
+
+    fn theme_motion(&self) -> Result<ThemeMotion, Error> {
+        let enable_animations = self
+            .config
+            .get(SETTINGS_GROUP, ENABLE_ANIMATIONS_KEY)
+            .and_then(parse_bool)
+            .ok_or(Error::Unavailable)?;
+
+        if enable_animations {
+            Ok(ThemeMotion::Normal)
+        } else {
+            Ok(ThemeMotion::Reduced)
+        }
+    }
+
+    fn theme_fonts(&self) -> Result<ThemeFonts, Error> {
+        // Only the interface font can be configured
+        let interface = self
+            .config
+            .get(SETTINGS_GROUP, FONT_NAME_KEY)
+            .and_then(fonts::parse_pango)
+            .ok_or(Error::Unavailable)?;
+
+        Ok(ThemeFonts {
+            interface: Some(interface),
+            ..Default::default()
+        })
+    }
+
+    fn text_scale(&self) -> Result<f32, Error> {
+        // -1 means the default DPI is used
+        self.config
+            .get(SETTINGS_GROUP, XFT_DPI_KEY)
+            .and_then(|dpi| dpi.parse::<f32>().ok())
+            .and_then(|dpi| super::dpi_scale(dpi / XFT_DPI_SCALE))
+            .ok_or(Error::Unavailable)
+    }
+
+    fn cursor_size(&self) -> Result<u32, Error> {
+        // Zero means the default size is used
+        self.config
+            .get(SETTINGS_GROUP, CURSOR_SIZE_KEY)
+            .and_then(|size| size.parse().ok())
+            .filter(|size| *size > 0)
+            .ok_or(Error::Unavailable)
+    }
+
+    fn gtk_theme(&self) -> Result<String, Error> {
+        // Override can include a variant (e.g. "Adwaita:dark")
+        self.theme_name()
+            .and_then(|name| name.split(':').next())
+            .map(str::to_string)
+            .ok_or(Error::Unavailable)
+    }
+}
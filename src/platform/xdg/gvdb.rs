@@ -0,0 +1,368 @@
+//! Reader for GVDB files, the format used by the dconf database
+//!
+//! Source: https://gitlab.gnome.org/GNOME/gvdb/-/blob/main/gvdb/gvdb-format.h
+
+/// File signature ("GVariant")
+const SIGNATURE: &[u8; 8] = b"GVariant";
+/// File signature when values are byteswapped ("GVariant" as big-endian words)
+const SWAPPED_SIGNATURE: &[u8; 8] = b"raVGtnai";
+
+const HEADER_SIZE: usize = 24;
+const HASH_HEADER_SIZE: usize = 8;
+const HASH_ITEM_SIZE: usize = 24;
+/// Bloom words count uses the lower 27 bits (the rest is the bloom shift)
+const BLOOM_WORDS_MASK: u32 = (1 << 27) - 1;
+/// Parent value for root items
+const NO_PARENT: u32 = u32::MAX;
+/// Item type for values
+const VALUE_TYPE: u8 = b'v';
+
+/// GVariant value stored in the database
+#[derive(Debug, Clone, PartialEq)]
+pub enum GValue {
+    /// Boolean (`b`)
+    Bool(bool),
+    /// Any integer type (`n`, `q`, `i`, `u`, `x`, `t`)
+    Int(i64),
+    /// Double (`d`)
+    Double(f64),
+    /// String (`s`)
+    String(String),
+}
+
+impl GValue {
+    /// Get the value as a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            GValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Get the value as an integer.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            GValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Get the value as a double (integers are converted).
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            GValue::Double(value) => Some(*value),
+            GValue::Int(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    /// Get the value as a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            GValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Hash table item
+struct Item {
+    hash: u32,
+    parent: u32,
+    key_start: usize,
+    key_size: usize,
+    kind: u8,
+    value_start: usize,
+    value_end: usize,
+}
+
+/// GVDB file, only the root hash table is used
+pub struct Gvdb {
+    data: Vec<u8>,
+    byteswapped: bool,
+    buckets: Vec<u32>,
+    items_start: usize,
+    items_count: usize,
+}
+
+impl Gvdb {
+    /// Parse the file contents.
+    pub fn new(data: Vec<u8>) -> Option<Self> {
+        let signature = data.get(..SIGNATURE.len())?;
+        let byteswapped = if signature == SIGNATURE {
+            false
+        } else if signature == SWAPPED_SIGNATURE {
+            true
+        } else {
+            return None;
+        };
+
+        // Root hash table pointer
+        let start = read_u32(&data, 16)? as usize;
+        let end = read_u32(&data, 20)? as usize;
+        if start < HEADER_SIZE || end > data.len() || start + HASH_HEADER_SIZE > end {
+            return None;
+        }
+
+        let bloom_words = (read_u32(&data, start)? & BLOOM_WORDS_MASK) as usize;
+        let buckets_count = read_u32(&data, start + 4)? as usize;
+
+        let buckets_start = start + HASH_HEADER_SIZE + bloom_words * 4;
+        let items_start = buckets_start + buckets_count * 4;
+        if items_start > end {
+            return None;
+        }
+
+        let buckets = (0..buckets_count)
+            .map(|bucket| read_u32(&data, buckets_start + bucket * 4))
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            items_count: (end - items_start) / HASH_ITEM_SIZE,
+            data,
+            byteswapped,
+            buckets,
+            items_start,
+        })
+    }
+
+    /// Get a value by its full key (e.g. `/org/gnome/desktop/interface/color-scheme`).
+    pub fn get(&self, key: &str) -> Option<GValue> {
+        if self.buckets.is_empty() {
+            return None;
+        }
+
+        let hash = hash_key(key);
+        let bucket = hash as usize % self.buckets.len();
+        let first = self.buckets[bucket] as usize;
+        let last = self
+            .buckets
+            .get(bucket + 1)
+            .map(|last| *last as usize)
+            .unwrap_or(self.items_count)
+            .min(self.items_count);
+
+        (first..last)
+            .filter_map(|index| self.item(index))
+            .find(|item| item.hash == hash && self.check_key(item, key.as_bytes()))
+            .filter(|item| item.kind == VALUE_TYPE)
+            .and_then(|item| {
+                let value = self.data.get(item.value_start..item.value_end)?;
+                decode_variant(value, self.byteswapped)
+            })
+    }
+
+    fn item(&self, index: usize) -> Option<Item> {
+        let offset = self.items_start + index * HASH_ITEM_SIZE;
+        let key_size = self.data.get(offset + 12..offset + 14)?;
+
+        Some(Item {
+            hash: read_u32(&self.data, offset)?,
+            parent: read_u32(&self.data, offset + 4)?,
+            key_start: read_u32(&self.data, offset + 8)? as usize,
+            key_size: u16::from_le_bytes([key_size[0], key_size[1]]) as usize,
+            kind: *self.data.get(offset + 14)?,
+            value_start: read_u32(&self.data, offset + 16)? as usize,
+            value_end: read_u32(&self.data, offset + 20)? as usize,
+        })
+    }
+
+    /// Check if the item key (including its parents) matches the given key
+    fn check_key(&self, item: &Item, key: &[u8]) -> bool {
+        let Some(item_key) = self
+            .data
+            .get(item.key_start..item.key_start + item.key_size)
+        else {
+            return false;
+        };
+
+        // Item stores only the end of the key, the rest is in its parents.
+        // Keys are never empty, which also stops parent loops in malformed files.
+        let Some(prefix) = key.strip_suffix(item_key).filter(|_| !item_key.is_empty()) else {
+            return false;
+        };
+
+        if item.parent == NO_PARENT {
+            return prefix.is_empty();
+        }
+
+        match self.item(item.parent as usize) {
+            Some(parent) => self.check_key(&parent, prefix),
+            None => false,
+        }
+    }
+}
+
+/// Read a little-endian u32 (file structure is always little-endian)
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Hash used by GVDB (djb2 with signed chars)
+fn hash_key(key: &str) -> u32 {
+    key.bytes().fold(5381u32, |hash, byte| {
+        hash.wrapping_mul(33).wrapping_add(byte as i8 as u32)
+    })
+}
+
+/// Decode a serialized GVariant of type `v`.
+///
+/// The value data is followed by a null byte and its type signature.
+fn decode_variant(data: &[u8], byteswapped: bool) -> Option<GValue> {
+    let separator = data.iter().rposition(|byte| *byte == 0)?;
+    let (value, signature) = (&data[..separator], &data[separator + 1..]);
+
+    // Fixed size values are stored in file endianness
+    macro_rules! number {
+        ($type:ty) => {{
+            let bytes = value.try_into().ok()?;
+            if byteswapped {
+                <$type>::from_be_bytes(bytes)
+            } else {
+                <$type>::from_le_bytes(bytes)
+            }
+        }};
+    }
+
+    match signature {
+        b"b" => Some(GValue::Bool(*value.first()? != 0)),
+        b"y" => Some(GValue::Int(*value.first()? as i64)),
+        b"n" => Some(GValue::Int(number!(i16) as i64)),
+        b"q" => Some(GValue::Int(number!(u16) as i64)),
+        b"i" => Some(GValue::Int(number!(i32) as i64)),
+        b"u" => Some(GValue::Int(number!(u32) as i64)),
+        b"x" => Some(GValue::Int(number!(i64))),
+        b"t" => Some(GValue::Int(number!(u64) as i64)),
+        b"d" => Some(GValue::Double(number!(f64))),
+        b"s" => {
+            // Strings are null-terminated
+            let value = value.strip_suffix(&[0])?;
+            String::from_utf8(value.to_vec()).ok().map(GValue::String)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// dconf user database written by `gsettings set`
+    const DATABASE: &[u8] = include_bytes!("fixtures/dconf-user");
+    /// Same database with byteswapped values
+    const SWAPPED_DATABASE: &[u8] = include_bytes!("fixtures/dconf-user-byteswapped");
+
+    const INTERFACE: &str = "/org/gnome/desktop/interface/";
+
+    fn expected() -> Vec<(String, GValue)> {
+        let interface = |key| format!("{INTERFACE}{key}");
+        vec![
+            (
+                interface("color-scheme"),
+                GValue::String("prefer-dark".into()),
+            ),
+            (
+                interface("gtk-theme"),
+                GValue::String("Adwaita-dark".into()),
+            ),
+            (
+                interface("font-name"),
+                GValue::String("Cantarell 11".into()),
+            ),
+            (interface("enable-animations"), GValue::Bool(false)),
+            (interface("cursor-size"), GValue::Int(32)),
+            (interface("text-scaling-factor"), GValue::Double(1.25)),
+            (
+                "/org/gnome/desktop/a11y/interface/high-contrast".into(),
+                GValue::Bool(true),
+            ),
+            (
+                "/org/gnome/desktop/wm/preferences/titlebar-font".into(),
+                GValue::String("Cantarell Bold 11".into()),
+            ),
+        ]
+    }
+
+    #[test]
+    fn nested_keys() {
+        let database = Gvdb::new(DATABASE.to_vec()).unwrap();
+
+        for (key, value) in expected() {
+            assert_eq!(database.get(&key), Some(value), "{key}");
+        }
+    }
+
+    #[test]
+    fn parent_chain() {
+        let database = Gvdb::new(DATABASE.to_vec()).unwrap();
+
+        // Same key name in another directory
+        assert_eq!(
+            database.get("/org/gnome/desktop/a11y/interface/color-scheme"),
+            None
+        );
+        assert_eq!(database.get("/org/gnome/color-scheme"), None);
+        assert_eq!(database.get("color-scheme"), None);
+        // Directories are not values
+        assert_eq!(database.get(INTERFACE), None);
+        assert_eq!(database.get("/"), None);
+        assert_eq!(database.get(&format!("{INTERFACE}missing")), None);
+    }
+
+    #[test]
+    fn byteswapped() {
+        let database = Gvdb::new(SWAPPED_DATABASE.to_vec()).unwrap();
+        assert!(database.byteswapped);
+
+        for (key, value) in expected() {
+            assert_eq!(database.get(&key), Some(value), "{key}");
+        }
+    }
+
+    #[test]
+    fn truncated() {
+        let expected = expected();
+
+        for length in 0..DATABASE.len() {
+            let Some(database) = Gvdb::new(DATABASE[..length].to_vec()) else {
+                continue;
+            };
+
+            // Values past the end are missing, never wrong
+            for (key, value) in &expected {
+                let result = database.get(key);
+                assert!(result.is_none() || result.as_ref() == Some(value), "{key}");
+            }
+        }
+
+        assert!(Gvdb::new(DATABASE[..HEADER_SIZE].to_vec()).is_none());
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(Gvdb::new(b"GVariant".to_vec()).is_none());
+        assert!(Gvdb::new(b"Variant\0".repeat(8)).is_none());
+
+        // Any corrupted byte must not panic (or loop)
+        for offset in 0..DATABASE.len() {
+            for byte in [0x00, 0xff] {
+                let mut data = DATABASE.to_vec();
+                data[offset] = byte;
+
+                if let Some(database) = Gvdb::new(data) {
+                    for (key, _) in expected() {
+                        database.get(&key);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn key_hash() {
+        // Values from gvdb `djb_hash`
+        assert_eq!(hash_key(""), 5381);
+        assert_eq!(hash_key("/"), 177620);
+    }
+}
@@ -0,0 +1,321 @@
+//! KDE global settings reader (`kdeglobals`)
+//!
+//! Files from `XDG_CONFIG_DIRS` are cascaded below the user file, like KConfig does.
+use std::path::PathBuf;
+
+use super::{fonts, provider::Provider};
+use crate::{
+    error::Error, format::kde, ini::Ini, Theme, ThemeColor, ThemeContrast, ThemeFont, ThemeFonts,
+    ThemeKind, ThemeMotion, ThemeScheme,
+};
+
+const KDEGLOBALS_FILE: &str = "kdeglobals";
+
+const GENERAL_GROUP: &str = "General";
+const KDE_GROUP: &str = "KDE";
+const WM_GROUP: &str = "WM";
+const WINDOW_GROUP: &str = "Colors:Window";
+const VIEW_GROUP: &str = "Colors:View";
+const SELECTION_GROUP: &str = "Colors:Selection";
+
+const COLOR_SCHEME_KEY: &str = "ColorScheme";
+const ACCENT_COLOR_KEY: &str = "AccentColor";
+const BACKGROUND_KEY: &str = "BackgroundNormal";
+const DECORATION_FOCUS_KEY: &str = "DecorationFocus";
+const ANIMATION_FACTOR_KEY: &str = "AnimationDurationFactor";
+const FONT_KEY: &str = "font";
+const FORCE_FONT_DPI_KEY: &str = "forceFontDPI";
+const FIXED_FONT_KEY: &str = "fixed";
+const TITLEBAR_FONT_KEY: &str = "activeFont";
+
+/// Color scheme name markers (compared in lowercase, without separators)
+const DARK_MARKER: &str = "dark";
+const LIGHT_MARKER: &str = "light";
+const HIGH_CONTRAST_MARKER: &str = "highcontrast";
+
+/// Check if a color is dark
+fn is_dark(color: ThemeColor) -> bool {
+    (color.red + color.green + color.blue) / 3.0 < 0.5
+}
+
+/// KDE global settings, merged from all configuration directories
+pub struct KdeGlobals {
+    config: Ini,
+}
+
+impl KdeGlobals {
+    /// Load and merge every `kdeglobals` file, returns `None` if there's none.
+    pub fn load() -> Option<Self> {
+        Ini::load_all(Self::paths()).map(|config| Self { config })
+    }
+
+    /// Get the paths of every `kdeglobals` file, in increasing order of preference.
+    pub fn paths() -> Vec<PathBuf> {
+        super::config_paths(&[KDEGLOBALS_FILE])
+    }
+
+    /// Get the active color scheme name.
+    pub fn color_scheme(&self) -> Option<&str> {
+        self.config.get(GENERAL_GROUP, COLOR_SCHEME_KEY)
+    }
+
+    /// Get the full theme, with the roles missing from the file taken from Breeze.
+    pub fn theme(&self) -> Result<Theme, Error> {
+        let scheme = self.theme_scheme_hint()?;
+        let contrast = self.theme_contrast().unwrap_or_default();
+        let mut theme = Theme::new(ThemeKind::Qt, scheme, contrast, self.theme_accent().ok());
+
+        if let Some(name) = self.color_scheme() {
+            theme.name = name.to_string();
+        }
+
+        for (role, color) in kde::palette_roles(&self.config) {
+            if let Some(slot) = theme.palette.role_mut(role) {
+                *slot = color;
+            }
+        }
+
+        Ok(theme)
+    }
+
+    fn color(&self, group: &str, key: &str) -> Option<ThemeColor> {
+        self.config.get(group, key).and_then(kde::parse_color)
+    }
+
+    fn font(&self, group: &str, key: &str) -> Option<ThemeFont> {
+        self.config.get(group, key).and_then(fonts::parse_qfont)
+    }
+
+    /// Get a color from the content area, or the window if not set
+    fn content_color(&self, key: &str) -> Option<ThemeColor> {
+        self.color(VIEW_GROUP, key)
+            .or_else(|| self.color(WINDOW_GROUP, key))
+    }
+
+    /// Get the color scheme name in lowercase, without separators
+    fn normalized_color_scheme(&self) -> Option<String> {
+        self.color_scheme().map(|name| {
+            name.chars()
+                .filter(char::is_ascii_alphanumeric)
+                .map(|char| char.to_ascii_lowercase())
+                .collect()
+        })
+    }
+}
+
+impl Provider for KdeGlobals {
+    // Plasma has no scheme preference, it's guessed from the color scheme
+    fn theme_scheme_hint(&self) -> Result<ThemeScheme, Error> {
+        let name = self.normalized_color_scheme();
+
+        // Scheme names usually tell the variant (e.g. "BreezeDark"), otherwise check the colors
+        match name.as_deref() {
+            Some(name) if name.contains(DARK_MARKER) => Ok(ThemeScheme::Dark),
+            Some(name) if name.contains(LIGHT_MARKER) => Ok(ThemeScheme::Light),
+            _ => match self.content_color(BACKGROUND_KEY) {
+                Some(background) if is_dark(background) => Ok(ThemeScheme::Dark),
+                Some(_) => Ok(ThemeScheme::Light),
+                // Named scheme without colors, like the default "Breeze"
+                None if name.is_some() => Ok(ThemeScheme::Light),
+                None => Err(Error::Unavailable),
+            },
+        }
+    }
+
+    fn theme_contrast(&self) -> Result<ThemeContrast, Error> {
+        let name = self.normalized_color_scheme().ok_or(Error::Unavailable)?;
+
+        if name.contains(HIGH_CONTRAST_MARKER) {
+            Ok(ThemeContrast::High)
+        } else {
+            Ok(ThemeContrast::Normal)
+        }
+    }
+
+    fn theme_accent(&self) -> Result<ThemeColor, Error> {
+        // Custom accent is only set if chosen by the user, the selection and focus colors follow it
+        self.color(GENERAL_GROUP, ACCENT_COLOR_KEY)
+            .or_else(|| self.color(SELECTION_GROUP, BACKGROUND_KEY))
+            .or_else(|| self.color(VIEW_GROUP, DECORATION_FOCUS_KEY))
+            .ok_or(Error::Unavailable)
+    }
+
+    fn theme_fonts(&self) -> Result<ThemeFonts, Error> {
+        // Plasma has no document font, the general one is used for everything
+        let interface = self.font(GENERAL_GROUP, FONT_KEY);
+
+        fonts::check_fonts(ThemeFonts {
+            document: interface.clone(),
+            interface,
+            monospace: self.font(GENERAL_GROUP, FIXED_FONT_KEY),
+            titlebar: self.font(WM_GROUP, TITLEBAR_FONT_KEY),
+        })
+    }
+
+    fn text_scale(&self) -> Result<f32, Error> {
+        // Zero means the DPI is not forced
+        self.config
+            .get(GENERAL_GROUP, FORCE_FONT_DPI_KEY)
+            .and_then(|dpi| dpi.parse().ok())
+            .and_then(super::dpi_scale)
+            .ok_or(Error::Unavailable)
+    }
+
+    fn theme_motion(&self) -> Result<ThemeMotion, Error> {
+        let factor: f64 = self
+            .config
+            .get(KDE_GROUP, ANIMATION_FACTOR_KEY)
+            .and_then(|factor| factor.parse().ok())
+            .ok_or(Error::Unavailable)?;
+
+        // Animations are disabled with a zero factor (slider set to "instant")
+        if factor <= 0.0 {
+            Ok(ThemeMotion::Reduced)
+        } else {
+            Ok(ThemeMotion::Normal)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn parse(content: &str) -> KdeGlobals {
+        KdeGlobals {
+            config: Ini::parse(content),
+        }
+    }
+
+    #[test]
+    fn scheme_names() {
+        let cases = [
+            ("BreezeDark", ThemeScheme::Dark, ThemeContrast::Normal),
+            ("Breeze Light", ThemeScheme::Light, ThemeContrast::Normal),
+            ("Breeze", ThemeScheme::Light, ThemeContrast::Normal),
+            (
+                "BreezeHighContrast",
+                ThemeScheme::Light,
+                ThemeContrast::High,
+            ),
+            (
+                "Breeze-High-Contrast-Dark",
+                ThemeScheme::Dark,
+                ThemeContrast::High,
+            ),
+        ];
+
+        for (name, scheme, contrast) in cases {
+            let kdeglobals = parse(&format!("[General]\nColorScheme={name}\n"));
+            assert_eq!(kdeglobals.theme_scheme_hint().unwrap(), scheme, "{name}");
+            assert_eq!(kdeglobals.theme_contrast().unwrap(), contrast, "{name}");
+        }
+    }
+
+    #[test]
+    fn scheme_from_background() {
+        // Name doesn't tell the variant, so the content background is used
+        let kdeglobals = parse(
+            "[General]\nColorScheme=Nordic\n\
+             [Colors:View]\nBackgroundNormal=46,52,64\n\
+             [Colors:Window]\nBackgroundNormal=239,240,241\n",
+        );
+        assert_eq!(kdeglobals.theme_scheme_hint().unwrap(), ThemeScheme::Dark);
+
+        // Window is used when the view has no background
+        let kdeglobals = parse("[Colors:Window]\nBackgroundNormal=239,240,241\n");
+        assert_eq!(kdeglobals.theme_scheme_hint().unwrap(), ThemeScheme::Light);
+
+        // Name is checked first
+        let kdeglobals =
+            parse("[General]\nColorScheme=MyLight\n[Colors:View]\nBackgroundNormal=0,0,0\n");
+        assert_eq!(kdeglobals.theme_scheme_hint().unwrap(), ThemeScheme::Light);
+
+        assert!(parse("").theme_scheme_hint().is_err());
+        assert!(parse("").theme_contrast().is_err());
+    }
+
+    #[test]
+    fn accent_fallbacks() {
+        let accent = "[General]\nAccentColor=233,100,60\n";
+        let selection = "[Colors:Selection]\nBackgroundNormal=61,174,233\n";
+        let focus = "[Colors:View]\nDecorationFocus=41,128,185\n";
+
+        let kdeglobals = parse(&format!("{accent}{selection}{focus}"));
+        assert_eq!(
+            kdeglobals.theme_accent().unwrap(),
+            ThemeColor::from_rgb8(233, 100, 60)
+        );
+
+        let kdeglobals = parse(&format!("{selection}{focus}"));
+        assert_eq!(
+            kdeglobals.theme_accent().unwrap(),
+            ThemeColor::from_rgb8(61, 174, 233)
+        );
+
+        let kdeglobals = parse(focus);
+        assert_eq!(
+            kdeglobals.theme_accent().unwrap(),
+            ThemeColor::from_rgb8(41, 128, 185)
+        );
+
+        assert!(parse("").theme_accent().is_err());
+    }
+
+    #[test]
+    fn animation_factor() {
+        let motion = |factor: &str| {
+            parse(&format!("[KDE]\nAnimationDurationFactor={factor}\n")).theme_motion()
+        };
+
+        assert_eq!(motion("0").unwrap(), ThemeMotion::Reduced);
+        assert_eq!(motion("0.0").unwrap(), ThemeMotion::Reduced);
+        assert_eq!(motion("0.5").unwrap(), ThemeMotion::Normal);
+        assert_eq!(motion("1").unwrap(), ThemeMotion::Normal);
+        assert!(motion("fast").is_err());
+        assert!(parse("").theme_motion().is_err());
+    }
+
+    #[test]
+    fn config_cascade() {
+        let root =
+            std::env::temp_dir().join(format!("system-theme-kdeglobals-{}", std::process::id()));
+        let (vendor, distribution, user) =
+            (root.join("vendor"), root.join("xdg"), root.join("home"));
+
+        for (dir, content) in [
+            (
+                &distribution,
+                "[General]\nColorScheme=BreezeDark\nfixed=Hack,10,-1,5,50,0,0,0,0,0\n",
+            ),
+            (
+                &vendor,
+                "[General]\nColorScheme=Vendor\nAccentColor=1,2,3\n",
+            ),
+            (&user, "[KDE]\nAnimationDurationFactor=0\n"),
+        ] {
+            fs::create_dir_all(dir).unwrap();
+            fs::write(dir.join(KDEGLOBALS_FILE), content).unwrap();
+        }
+
+        // XDG_CONFIG_DIRS=vendor:xdg, the first directory is preferred
+        let paths =
+            super::super::cascade_paths(vec![vendor, distribution], Some(user), &[KDEGLOBALS_FILE]);
+        let kdeglobals = KdeGlobals {
+            config: Ini::load_all(paths).unwrap(),
+        };
+        fs::remove_dir_all(root).unwrap();
+
+        assert_eq!(kdeglobals.color_scheme(), Some("Vendor"));
+        assert_eq!(
+            kdeglobals.theme_accent().unwrap(),
+            ThemeColor::from_rgb8(1, 2, 3)
+        );
+        assert_eq!(kdeglobals.theme_motion().unwrap(), ThemeMotion::Reduced);
+        assert_eq!(
+            kdeglobals.theme_fonts().unwrap().monospace.unwrap().family,
+            "Hack"
+        );
+    }
+}
@@ -0,0 +1,1081 @@
+mod desktop;
+mod fonts;
+mod gsettings;
+mod gtksettings;
+mod gvdb;
+mod kdeglobals;
+mod provider;
+#[cfg(target_os = "linux")]
+mod watcher;
+mod xresources;
+
+use async_stream::stream;
+use futures_core::stream::Stream;
+use futures_util::StreamExt;
+use kdeglobals::KdeGlobals;
+use provider::{Provider, Providers};
+use std::{cell::LazyCell, collections::HashMap, env, future::Future, path::PathBuf, pin::pin};
+use tokio::sync::OnceCell;
+use zbus::{
+    fdo::DBusProxy,
+    message::Message,
+    names::OwnedBusName,
+    proxy::SignalStream,
+    zvariant::{OwnedValue, Value},
+    Connection, Proxy,
+};
+
+use super::ThemeSettings;
+use crate::{
+    error::Error, Desktop, Theme, ThemeAccentShades, ThemeChange, ThemeColor, ThemeContrast,
+    ThemeFonts, ThemeKind, ThemeMotion, ThemeScaling, ThemeScheme,
+};
+
+const DESKTOP_PORTAL_DEST: &str = "org.freedesktop.portal.Desktop";
+const DESKTOP_PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+const SETTINGS_INTERFACE: &str = "org.freedesktop.portal.Settings";
+const READ_METHOD: &str = "ReadOne";
+const LEGACY_READ_METHOD: &str = "Read";
+const READ_ALL_METHOD: &str = "ReadAll";
+const VERSION_PROPERTY: &str = "version";
+const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
+const GET_PROPERTY_METHOD: &str = "Get";
+const CHANGE_SIGNAL: &str = "SettingChanged";
+const APPERANCE_NAMESPACE: &str = "org.freedesktop.appearance";
+const GNOME_INTERFACE_NAMESPACE: &str = "org.gnome.desktop.interface";
+const GNOME_WM_NAMESPACE: &str = "org.gnome.desktop.wm.preferences";
+
+const COLOR_SCHEME_KEY: &str = "color-scheme";
+const CONTRAST_KEY: &str = "contrast";
+const ACCENT_COLOR_KEY: &str = "accent-color";
+const REDUCED_MOTION_KEY: &str = "reduced-motion";
+const FONT_NAME_KEY: &str = "font-name";
+const DOCUMENT_FONT_NAME_KEY: &str = "document-font-name";
+const MONOSPACE_FONT_NAME_KEY: &str = "monospace-font-name";
+const TITLEBAR_FONT_KEY: &str = "titlebar-font";
+const TEXT_SCALING_KEY: &str = "text-scaling-factor";
+const CURSOR_SIZE_KEY: &str = "cursor-size";
+const GTK_THEME_KEY: &str = "gtk-theme";
+
+const PORTAL_NOT_FOUND: &str = "org.freedesktop.portal.Error.NotFound";
+const DBUS_UNKNOWN_SERVICE: &str = "org.freedesktop.DBus.Error.ServiceUnknown";
+const DBUS_UNKNOWN_METHOD: &str = "org.freedesktop.DBus.Error.UnknownMethod";
+
+const GTK_PORTAL_IMPL: &str = "org.freedesktop.impl.portal.desktop.gtk";
+const PORTAL_IMPL_PREFIX: &str = "org.freedesktop.impl.portal.desktop.";
+
+/// First settings interface version providing `ReadOne`
+const READ_METHOD_VERSION: u32 = 2;
+
+/// Reference DPI, text is scaled by the configured DPI relative to it
+const DEFAULT_DPI: f32 = 96.0;
+
+impl From<zbus::Error> for Error {
+    fn from(value: zbus::Error) -> Self {
+        match &value {
+            zbus::Error::InterfaceNotFound => Error::Unsupported,
+            zbus::Error::Unsupported => Error::Unsupported,
+            zbus::Error::MethodError(name, _, _) => {
+                let name_str = name.as_str();
+                // Errors that can be returned if not supported by the platform
+                if name_str == PORTAL_NOT_FOUND
+                    || name_str == DBUS_UNKNOWN_SERVICE
+                    || name_str == DBUS_UNKNOWN_METHOD
+                {
+                    Error::Unsupported
+                } else {
+                    Error::from_platform(value)
+                }
+            }
+            _ => Error::from_platform(value),
+        }
+    }
+}
+
+/// Settings values grouped by namespace, as returned by `ReadAll`
+type SettingsNamespaces = HashMap<String, HashMap<String, OwnedValue>>;
+
+/// Get the user configuration directory
+fn config_home() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+}
+
+/// Get the system configuration directories, in decreasing order of preference
+fn config_dirs() -> Vec<PathBuf> {
+    let dirs = env::var("XDG_CONFIG_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/etc/xdg".to_string());
+
+    dirs.split(':')
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Get the configuration file paths in every directory, in increasing order of preference.
+///
+/// Files are cascaded, so the user ones come last.
+fn config_paths(paths: &[&str]) -> Vec<PathBuf> {
+    cascade_paths(config_dirs(), config_home(), paths)
+}
+
+/// Get the file paths in the system directories (in decreasing order of preference) and the user
+/// one, in increasing order of preference
+fn cascade_paths(mut dirs: Vec<PathBuf>, home: Option<PathBuf>, paths: &[&str]) -> Vec<PathBuf> {
+    dirs.reverse();
+    dirs.extend(home);
+
+    dirs.iter()
+        .flat_map(|dir| paths.iter().map(|path| dir.join(path)))
+        .collect()
+}
+
+/// Get the changes to the configuration files read by the providers
+#[cfg(target_os = "linux")]
+fn file_changes() -> impl Stream<Item = ()> {
+    watcher::watch(Providers::paths())
+}
+
+/// Get the changes to the configuration files (not supported, inotify is Linux only)
+#[cfg(not(target_os = "linux"))]
+fn file_changes() -> impl Stream<Item = ()> {
+    futures_util::stream::empty()
+}
+
+/// Use the configuration files if the portal doesn't provide the setting (or has no preference)
+fn or_providers<T>(
+    result: Result<T, Error>,
+    providers: &LazyCell<Providers, impl FnOnce() -> Providers>,
+    read: impl Fn(&dyn Provider) -> Result<T, Error>,
+) -> Result<T, Error> {
+    match result {
+        Err(err @ (Error::Unsupported | Error::Unavailable)) => {
+            // Keep the portal error if no file has the setting either
+            providers.get(read).map_err(|file_err| match file_err {
+                Error::Unsupported => err,
+                file_err => file_err,
+            })
+        }
+        result => result,
+    }
+}
+
+/// Resolve the portal scheme preference with the configuration files.
+///
+/// Portals of desktops without the setting (e.g. GTK on Xfce) report "no preference", so the
+/// scheme is guessed from the theme in use, and it stays unknown only if nothing tells it.
+fn resolve_scheme(
+    scheme: Result<Option<ThemeScheme>, Error>,
+    providers: &LazyCell<Providers, impl FnOnce() -> Providers>,
+) -> Result<Option<ThemeScheme>, Error> {
+    match scheme {
+        Ok(None) => Ok(providers.get(|provider| provider.theme_scheme_hint()).ok()),
+        scheme => or_providers(scheme, providers, |provider| {
+            provider.theme_scheme_hint().map(Some)
+        }),
+    }
+}
+
+/// Take the GTK theme name from the GNOME interface settings
+fn take_gtk_theme(namespaces: &mut SettingsNamespaces) -> Option<String> {
+    let value = namespaces
+        .get_mut(GNOME_INTERFACE_NAMESPACE)?
+        .remove(GTK_THEME_KEY)?;
+
+    convert_value(value).ok()
+}
+
+/// Use the GTK theme name from the portal, or read it from the configuration files.
+///
+/// Files are read on the blocking thread pool, so the caller's executor isn't stalled.
+async fn gtk_theme_or_files(theme: Option<String>) -> Option<String> {
+    match theme {
+        Some(theme) => Some(theme),
+        None => blocking::unblock(Providers::gtk_theme).await.ok(),
+    }
+}
+
+/// Convert a configured DPI to a text scale factor (unset or invalid -> `None`)
+fn dpi_scale(dpi: f32) -> Option<f32> {
+    (dpi > 0.0).then(|| dpi / DEFAULT_DPI)
+}
+
+/// Copy an error for settings read by the same request (errors can't be cloned).
+///
+/// Only unsupported is kept, anything else means the setting is unavailable.
+fn copy_error(err: &Error) -> Error {
+    match err {
+        Error::Unsupported => Error::Unsupported,
+        _ => Error::Unavailable,
+    }
+}
+
+/// Check if color component is valid
+fn check_color_component(component: f64) -> bool {
+    (0.0..=1.0).contains(&component)
+}
+
+/// Unwrap the extra variant returned by the legacy `Read` method.
+///
+/// Some portal implementations don't add it, so a plain value is returned as is.
+fn unwrap_legacy_value(value: OwnedValue) -> Result<OwnedValue, Error> {
+    match &*value {
+        Value::Value(inner) => inner.try_to_owned().map_err(Error::from_platform),
+        _ => Ok(value),
+    }
+}
+
+/// Decode the value replied by a read method (`legacy` for the deprecated `Read` method)
+fn decode_read_reply(value: OwnedValue, legacy: bool) -> Result<OwnedValue, Error> {
+    if legacy {
+        unwrap_legacy_value(value)
+    } else {
+        Ok(value)
+    }
+}
+
+/// Convert a settings value to the desired type (invalid -> not configured)
+fn convert_value<T: TryFrom<OwnedValue>>(value: OwnedValue) -> Result<T, Error> {
+    value.try_into().map_err(|_| Error::Unavailable)
+}
+
+/// Decode the color scheme setting (`None` if the user has no preference)
+fn decode_scheme(value: OwnedValue) -> Result<Option<ThemeScheme>, Error> {
+    let scheme: u32 = convert_value(value)?;
+
+    // 0 = no preference, 1 = dark, 2 = light (unknown values mean no preference)
+    match scheme {
+        1 => Ok(Some(ThemeScheme::Dark)),
+        2 => Ok(Some(ThemeScheme::Light)),
+        _ => Ok(None),
+    }
+}
+
+/// Decode the contrast setting
+fn decode_contrast(value: OwnedValue) -> Result<ThemeContrast, Error> {
+    let contrast: u32 = convert_value(value)?;
+
+    // 1 = high
+    if contrast == 1 {
+        Ok(ThemeContrast::High)
+    } else {
+        Ok(ThemeContrast::Normal)
+    }
+}
+
+/// Decode the accent color setting
+fn decode_accent(value: OwnedValue) -> Result<ThemeColor, Error> {
+    let accent: (f64, f64, f64) = convert_value(value)?;
+
+    // Check color components range (invalid -> not configured)
+    if !check_color_component(accent.0)
+        || !check_color_component(accent.1)
+        || !check_color_component(accent.2)
+    {
+        return Err(Error::Unavailable);
+    }
+
+    Ok(ThemeColor {
+        red: accent.0 as f32,
+        green: accent.1 as f32,
+        blue: accent.2 as f32,
+        alpha: 1.0,
+    })
+}
+
+/// Decode the reduced motion setting
+fn decode_motion(value: OwnedValue) -> Result<ThemeMotion, Error> {
+    let motion: u32 = convert_value(value)?;
+
+    // 1 = reduced
+    if motion == 1 {
+        Ok(ThemeMotion::Reduced)
+    } else {
+        Ok(ThemeMotion::Normal)
+    }
+}
+
+/// Decode the GNOME text scaling factor setting
+fn decode_text_scale(value: OwnedValue) -> Result<f32, Error> {
+    let factor: f64 = convert_value(value)?;
+
+    if factor > 0.0 {
+        Ok(factor as f32)
+    } else {
+        Err(Error::Unavailable)
+    }
+}
+
+/// Decode the GNOME cursor size setting
+fn decode_cursor_size(value: OwnedValue) -> Result<u32, Error> {
+    let size: i32 = convert_value(value)?;
+
+    u32::try_from(size)
+        .ok()
+        .filter(|size| *size > 0)
+        .ok_or(Error::Unavailable)
+}
+
+/// Decode the GNOME font settings (Pango descriptions)
+fn decode_fonts(mut namespaces: SettingsNamespaces) -> Result<ThemeFonts, Error> {
+    let mut interface = namespaces
+        .remove(GNOME_INTERFACE_NAMESPACE)
+        .unwrap_or_default();
+    let mut wm = namespaces.remove(GNOME_WM_NAMESPACE).unwrap_or_default();
+
+    let font = |values: &mut HashMap<String, OwnedValue>, key| {
+        let description: String = convert_value(values.remove(key)?).ok()?;
+        fonts::parse_pango(&description)
+    };
+
+    let fonts = ThemeFonts {
+        interface: font(&mut interface, FONT_NAME_KEY),
+        document: font(&mut interface, DOCUMENT_FONT_NAME_KEY),
+        monospace: font(&mut interface, MONOSPACE_FONT_NAME_KEY),
+        titlebar: font(&mut wm, TITLEBAR_FONT_KEY),
+    };
+
+    // Namespaces are only provided by GNOME based portals
+    if fonts == ThemeFonts::default() {
+        Err(Error::Unsupported)
+    } else {
+        Ok(fonts)
+    }
+}
+
+/// Decode a `SettingChanged` signal, ignoring settings unrelated to the theme
+fn decode_change(message: &Message) -> Option<Source> {
+    let (namespace, key, value): (String, String, OwnedValue) =
+        message.body().deserialize().ok()?;
+
+    // Scaling is reported as a whole, so the other setting has to be read again
+    if namespace == GNOME_INTERFACE_NAMESPACE {
+        return matches!(key.as_str(), TEXT_SCALING_KEY | CURSOR_SIZE_KEY)
+            .then_some(Source::Reload);
+    }
+
+    if namespace != APPERANCE_NAMESPACE {
+        return None;
+    }
+
+    let change = match key.as_str() {
+        COLOR_SCHEME_KEY => {
+            // No preference is resolved like when reading the setting
+            let scheme = resolve_scheme(decode_scheme(value), &LazyCell::new(Providers::load));
+            scheme.ok().map(ThemeChange::Scheme)
+        }
+        CONTRAST_KEY => decode_contrast(value).ok().map(ThemeChange::Contrast),
+        // Invalid accent means it is no longer configured
+        ACCENT_COLOR_KEY => Some(ThemeChange::Accent(decode_accent(value).ok())),
+        REDUCED_MOTION_KEY => decode_motion(value).ok().map(ThemeChange::Motion),
+        _ => None,
+    };
+
+    change.map(Source::Portal)
+}
+
+#[derive(Clone)]
+/// Source of a theme event
+enum Source {
+    /// Setting changed signal from the portal
+    Portal(ThemeChange),
+    /// Settings changed without their new values (configuration file saved, or partial setting)
+    Reload,
+}
+
+/// Last known theme settings, used to only report actual changes
+#[derive(Clone)]
+struct Snapshot {
+    scheme: Option<Option<ThemeScheme>>,
+    contrast: Option<ThemeContrast>,
+    /// Accent color (`Some(None)` if not configured, `None` if it couldn't be read)
+    accent: Option<Option<ThemeColor>>,
+    motion: Option<ThemeMotion>,
+    scaling: Option<ThemeScaling>,
+    theme: Option<Theme>,
+}
+
+impl Snapshot {
+    fn new(
+        (settings, motion): (ThemeSettings, Result<ThemeMotion, Error>),
+        scaling: Result<ThemeScaling, Error>,
+    ) -> Self {
+        Self {
+            scheme: settings.scheme.ok(),
+            contrast: settings.contrast.ok(),
+            accent: match settings.accent {
+                Ok(accent) => Some(Some(accent)),
+                Err(Error::Unsupported | Error::Unavailable) => Some(None),
+                Err(_) => None,
+            },
+            motion: motion.ok(),
+            scaling: scaling.ok(),
+            theme: settings.theme,
+        }
+    }
+
+    /// Get a copy of the snapshot with a change applied
+    fn with_change(&self, change: ThemeChange) -> Self {
+        let mut next = self.clone();
+        match change {
+            ThemeChange::Scheme(scheme) => next.scheme = Some(scheme),
+            ThemeChange::Contrast(contrast) => next.contrast = Some(contrast),
+            ThemeChange::Accent(accent) => next.accent = Some(accent),
+            ThemeChange::Motion(motion) => next.motion = Some(motion),
+            ThemeChange::Scaling(scaling) => next.scaling = Some(scaling),
+        }
+        next
+    }
+
+    /// Replace the snapshot, returning what changed (`None` if only the desktop theme did).
+    ///
+    /// Settings that couldn't be read keep their last known value.
+    fn update(&mut self, next: Self) -> Vec<Option<ThemeChange>> {
+        let mut changes = Vec::new();
+        let next = Self {
+            scheme: next.scheme.or(self.scheme),
+            contrast: next.contrast.or(self.contrast),
+            accent: next.accent.or(self.accent),
+            motion: next.motion.or(self.motion),
+            scaling: next.scaling.or(self.scaling),
+            theme: next.theme,
+        };
+
+        if let Some(scheme) = next.scheme.filter(|scheme| Some(*scheme) != self.scheme) {
+            changes.push(Some(ThemeChange::Scheme(scheme)));
+        }
+        if let Some(contrast) = next
+            .contrast
+            .filter(|contrast| Some(*contrast) != self.contrast)
+        {
+            changes.push(Some(ThemeChange::Contrast(contrast)));
+        }
+        if let Some(accent) = next.accent.filter(|accent| Some(*accent) != self.accent) {
+            changes.push(Some(ThemeChange::Accent(accent)));
+        }
+        if let Some(motion) = next.motion.filter(|motion| Some(*motion) != self.motion) {
+            changes.push(Some(ThemeChange::Motion(motion)));
+        }
+        if let Some(scaling) = next
+            .scaling
+            .filter(|scaling| Some(*scaling) != self.scaling)
+        {
+            changes.push(Some(ThemeChange::Scaling(scaling)));
+        }
+        if changes.is_empty() && next.theme != self.theme {
+            changes.push(None);
+        }
+
+        *self = next;
+        changes
+    }
+}
+
+#[derive(Clone)]
+pub struct Platform {
+    conn: Option<Connection>,
+    version: OnceCell<u32>,
+    portal_impls: OnceCell<Vec<String>>,
+}
+
+impl Platform {
+    pub fn new() -> Result<Self, Error> {
+        // Settings can still be read from files if there's no session bus
+        let conn = zbus::block_on(Connection::session()).ok();
+
+        Ok(Self {
+            conn,
+            version: OnceCell::new(),
+            portal_impls: OnceCell::new(),
+        })
+    }
+
+    pub fn theme_kind(&self) -> Result<ThemeKind, Error> {
+        zbus::block_on(self.theme_kind_async())
+    }
+
+    pub fn theme_scheme(&self) -> Result<ThemeScheme, Error> {
+        zbus::block_on(self.theme_scheme_async())
+    }
+
+    pub fn theme_scheme_preference(&self) -> Result<Option<ThemeScheme>, Error> {
+        zbus::block_on(self.theme_scheme_preference_async())
+    }
+
+    pub fn theme_contrast(&self) -> Result<ThemeContrast, Error> {
+        zbus::block_on(self.theme_contrast_async())
+    }
+
+    pub fn theme_accent(&self) -> Result<ThemeColor, Error> {
+        zbus::block_on(self.theme_accent_async())
+    }
+
+    pub fn theme_accent_shades(&self) -> Result<ThemeAccentShades, Error> {
+        zbus::block_on(self.theme_accent_shades_async())
+    }
+
+    pub fn theme_motion(&self) -> Result<ThemeMotion, Error> {
+        zbus::block_on(self.theme_motion_async())
+    }
+
+    pub fn theme_settings(&self) -> ThemeSettings {
+        zbus::block_on(self.theme_settings_async())
+    }
+
+    pub fn theme_fonts(&self) -> Result<ThemeFonts, Error> {
+        zbus::block_on(self.theme_fonts_async())
+    }
+
+    pub fn theme_scaling(&self) -> Result<ThemeScaling, Error> {
+        zbus::block_on(self.theme_scaling_async())
+    }
+
+    pub fn desktop(&self) -> Result<Desktop, Error> {
+        zbus::block_on(self.desktop_async())
+    }
+
+    pub async fn theme_kind_async(&self) -> Result<ThemeKind, Error> {
+        self.detect_kind(async {
+            let namespaces = self.get_all_settings(&[GNOME_INTERFACE_NAMESPACE]).await;
+            gtk_theme_or_files(namespaces.ok().as_mut().and_then(take_gtk_theme)).await
+        })
+        .await
+    }
+
+    /// Detect the theme kind, the GTK theme name is only awaited if the desktop needs it
+    async fn detect_kind(
+        &self,
+        gtk_theme: impl Future<Output = Option<String>>,
+    ) -> Result<ThemeKind, Error> {
+        let desktop = self.desktop_async().await.ok();
+        let kind = desktop.and_then(desktop::toolkit);
+
+        // Generic GTK desktops (and window managers) can use a theme with its own palette
+        if matches!(kind, None | Some(ThemeKind::Gtk)) {
+            if let Some(kind) = gtk_theme.await.as_deref().and_then(desktop::gtk_theme_kind) {
+                return Ok(kind);
+            }
+            if desktop::is_ubuntu() {
+                return Ok(ThemeKind::Yaru);
+            }
+        }
+
+        // Window managers may have a toolkit configured for Qt
+        if let Some(kind) = kind.or_else(desktop::qt_platform_theme) {
+            return Ok(kind);
+        }
+
+        if self
+            .get_portal_impls()
+            .await?
+            .iter()
+            .any(|name| name == GTK_PORTAL_IMPL)
+        {
+            // If we have GTK Portal, we're using GTK
+            Ok(ThemeKind::Gtk)
+        } else {
+            // Anything else should be Qt
+            Ok(ThemeKind::Qt)
+        }
+    }
+
+    pub async fn theme_scheme_async(&self) -> Result<ThemeScheme, Error> {
+        // No preference uses the default scheme, like the portal did before it had the option
+        self.theme_scheme_preference_async()
+            .await
+            .map(Option::unwrap_or_default)
+    }
+
+    pub async fn theme_scheme_preference_async(&self) -> Result<Option<ThemeScheme>, Error> {
+        let scheme = self
+            .get_settings_apperance(COLOR_SCHEME_KEY)
+            .await
+            .and_then(decode_scheme);
+
+        resolve_scheme(scheme, &LazyCell::new(Providers::load))
+    }
+
+    pub async fn theme_contrast_async(&self) -> Result<ThemeContrast, Error> {
+        let contrast = self
+            .get_settings_apperance(CONTRAST_KEY)
+            .await
+            .and_then(decode_contrast);
+
+        or_providers(contrast, &LazyCell::new(Providers::load), |provider| {
+            provider.theme_contrast()
+        })
+    }
+
+    pub async fn theme_accent_async(&self) -> Result<ThemeColor, Error> {
+        let accent = self
+            .get_settings_apperance(ACCENT_COLOR_KEY)
+            .await
+            .and_then(decode_accent);
+
+        or_providers(accent, &LazyCell::new(Providers::load), |provider| {
+            provider.theme_accent()
+        })
+    }
+
+    pub async fn theme_accent_shades_async(&self) -> Result<ThemeAccentShades, Error> {
+        // Desktops only provide the accent color
+        self.theme_accent_async()
+            .await
+            .map(ThemeAccentShades::from_accent)
+    }
+
+    pub async fn theme_motion_async(&self) -> Result<ThemeMotion, Error> {
+        let motion = self
+            .get_settings_apperance(REDUCED_MOTION_KEY)
+            .await
+            .and_then(decode_motion);
+
+        or_providers(motion, &LazyCell::new(Providers::load), |provider| {
+            provider.theme_motion()
+        })
+    }
+
+    pub async fn theme_fonts_async(&self) -> Result<ThemeFonts, Error> {
+        let fonts = self
+            .get_all_settings(&[GNOME_INTERFACE_NAMESPACE, GNOME_WM_NAMESPACE])
+            .await
+            .and_then(decode_fonts);
+
+        or_providers(fonts, &LazyCell::new(Providers::load), |provider| {
+            provider.theme_fonts()
+        })
+    }
+
+    pub async fn theme_scaling_async(&self) -> Result<ThemeScaling, Error> {
+        let (text, cursor_size) = match self.get_all_settings(&[GNOME_INTERFACE_NAMESPACE]).await {
+            Ok(mut namespaces) => {
+                // A missing key means the portal doesn't support it
+                let mut values = namespaces
+                    .remove(GNOME_INTERFACE_NAMESPACE)
+                    .unwrap_or_default();
+                let mut take = |key| values.remove(key).ok_or(Error::Unsupported);
+                (
+                    take(TEXT_SCALING_KEY).and_then(decode_text_scale),
+                    take(CURSOR_SIZE_KEY).and_then(decode_cursor_size),
+                )
+            }
+            Err(err) => (Err(copy_error(&err)), Err(err)),
+        };
+
+        let providers = LazyCell::new(Providers::load);
+        let text = or_providers(text, &providers, |provider| provider.text_scale());
+        let cursor_size = or_providers(cursor_size, &providers, |provider| provider.cursor_size());
+
+        // Settings can come from different sources, only fail if neither is known
+        match (text, cursor_size) {
+            (Err(err), Err(_)) => Err(err),
+            (text, cursor_size) => Ok(ThemeScaling {
+                text: text.unwrap_or(1.0),
+                cursor_size: cursor_size.ok(),
+            }),
+        }
+    }
+
+    pub async fn theme_settings_async(&self) -> ThemeSettings {
+        self.read_settings().await.0
+    }
+
+    /// Read the theme settings and the motion preference, as both come from the same request
+    async fn read_settings(&self) -> (ThemeSettings, Result<ThemeMotion, Error>) {
+        // The GTK theme name used to detect the kind comes with the appearance settings
+        let mut namespaces = self
+            .get_all_settings(&[APPERANCE_NAMESPACE, GNOME_INTERFACE_NAMESPACE])
+            .await;
+        let gtk_theme = namespaces.as_mut().ok().and_then(take_gtk_theme);
+        let kind = self.detect_kind(gtk_theme_or_files(gtk_theme)).await;
+
+        // Namespace is omitted if it has no keys
+        let appearance = namespaces
+            .map(|mut namespaces| namespaces.remove(APPERANCE_NAMESPACE).unwrap_or_default());
+
+        let (scheme, contrast, accent, motion) = match appearance {
+            Ok(mut values) => {
+                // A missing key means the portal doesn't support it
+                let mut take = |key| values.remove(key).ok_or(Error::Unsupported);
+                (
+                    take(COLOR_SCHEME_KEY).and_then(decode_scheme),
+                    take(CONTRAST_KEY).and_then(decode_contrast),
+                    take(ACCENT_COLOR_KEY).and_then(decode_accent),
+                    take(REDUCED_MOTION_KEY).and_then(decode_motion),
+                )
+            }
+            Err(err) => {
+                // Only the accent keeps the original error
+                let (scheme, contrast, motion) = (
+                    Err(copy_error(&err)),
+                    Err(copy_error(&err)),
+                    Err(copy_error(&err)),
+                );
+                (scheme, contrast, Err(err), motion)
+            }
+        };
+
+        // Files are only loaded if the portal can't provide some setting
+        let providers = LazyCell::new(Providers::load);
+        let scheme = resolve_scheme(scheme, &providers);
+        let contrast = or_providers(contrast, &providers, |provider| provider.theme_contrast());
+        let accent = or_providers(accent, &providers, |provider| provider.theme_accent());
+        let motion = or_providers(motion, &providers, |provider| provider.theme_motion());
+
+        // Plasma colors are fully configurable, so use the actual ones
+        let theme = match kind {
+            Ok(ThemeKind::Qt) => KdeGlobals::load().and_then(|kdeglobals| kdeglobals.theme().ok()),
+            _ => None,
+        };
+
+        let settings = ThemeSettings {
+            kind,
+            scheme,
+            contrast,
+            accent,
+            accent_shades: None,
+            theme,
+        };
+
+        (settings, motion)
+    }
+
+    /// Read every setting reported by the subscriptions
+    async fn read_snapshot(&self) -> Snapshot {
+        let (settings, scaling) =
+            futures_util::join!(self.read_settings(), self.theme_scaling_async());
+
+        Snapshot::new(settings, scaling)
+    }
+
+    pub async fn desktop_async(&self) -> Result<Desktop, Error> {
+        if let Some(desktop) = desktop::from_env() {
+            return Ok(desktop);
+        }
+
+        // Portal implementations are only running in their own desktop
+        let names = self.get_portal_impls().await?;
+
+        Ok(desktop::from_portal_impls(&names).unwrap_or(Desktop::Unknown))
+    }
+
+    pub fn subscribe(&self) -> impl Stream<Item = ()> {
+        self.events().map(|_| ())
+    }
+
+    pub fn subscribe_changes(&self) -> impl Stream<Item = ThemeChange> {
+        self.events().filter_map(futures_util::future::ready)
+    }
+
+    /// Get the theme events from the portal and the configuration files.
+    ///
+    /// Yields `None` for changes not covered by [`ThemeChange`] (e.g. desktop palette colors).
+    fn events(&self) -> impl Stream<Item = Option<ThemeChange>> {
+        let platform = self.clone();
+
+        // Both sources are driven by whoever polls the subscription,
+        // so no background thread or runtime is required.
+        stream! {
+            // Subscribe before reading the settings, so changes made in between aren't missed
+            let portal = platform.portal_changes().await;
+            let files = file_changes().map(|_| Source::Reload);
+            let mut last = platform.read_snapshot().await;
+
+            let mut sources = pin!(futures_util::stream::select(portal, files));
+
+            while let Some(source) = sources.next().await {
+                let next = match source {
+                    Source::Portal(change) => last.with_change(change),
+                    // Source doesn't tell what changed, so read everything again
+                    Source::Reload => platform.read_snapshot().await,
+                };
+
+                for change in last.update(next) {
+                    yield change;
+                }
+            }
+        }
+    }
+
+    /// Subscribe to the portal changes (the stream is empty if the portal can't be reached)
+    async fn portal_changes(&self) -> impl Stream<Item = Source> {
+        let signal = self.receive_changes().await;
+
+        stream! {
+            let Some(mut signal) = signal else {
+                return;
+            };
+
+            while let Some(message) = signal.next().await {
+                if let Some(source) = decode_change(&message) {
+                    yield source;
+                }
+            }
+        }
+    }
+
+    async fn receive_changes(&self) -> Option<SignalStream<'static>> {
+        let proxy = Proxy::new(
+            self.conn.as_ref()?,
+            DESKTOP_PORTAL_DEST,
+            DESKTOP_PORTAL_PATH,
+            SETTINGS_INTERFACE,
+        )
+        .await
+        .ok()?;
+
+        proxy.receive_signal(CHANGE_SIGNAL).await.ok()
+    }
+
+    fn conn(&self) -> Result<&Connection, Error> {
+        // Without a session bus, there's no portal to talk to
+        self.conn.as_ref().ok_or(Error::Unsupported)
+    }
+
+    /// Get the running portal implementations.
+    ///
+    /// They run as long as the session, so the list is kept once one is found (none could mean
+    /// the portal is still starting them).
+    async fn get_portal_impls(&self) -> Result<Vec<String>, Error> {
+        if let Some(names) = self.portal_impls.get() {
+            return Ok(names.clone());
+        }
+
+        let names: Vec<String> = self
+            .get_bus_names()
+            .await?
+            .iter()
+            .map(|name| name.as_str())
+            .filter(|name| name.starts_with(PORTAL_IMPL_PREFIX))
+            .map(str::to_string)
+            .collect();
+
+        if !names.is_empty() {
+            let _ = self.portal_impls.set(names.clone());
+        }
+        Ok(names)
+    }
+
+    async fn get_bus_names(&self) -> Result<Vec<OwnedBusName>, Error> {
+        let proxy = DBusProxy::new(self.conn()?).await?;
+        proxy.list_names().await.map_err(Error::from_platform)
+    }
+
+    async fn get_settings_version(&self) -> Result<u32, Error> {
+        // Interface version doesn't change while the portal is running
+        self.version
+            .get_or_try_init(|| async {
+                let response = self
+                    .conn()?
+                    .call_method(
+                        Some(DESKTOP_PORTAL_DEST),
+                        DESKTOP_PORTAL_PATH,
+                        Some(PROPERTIES_INTERFACE),
+                        GET_PROPERTY_METHOD,
+                        &(SETTINGS_INTERFACE, VERSION_PROPERTY),
+                    )
+                    .await?;
+
+                let value = response
+                    .body()
+                    .deserialize::<OwnedValue>()
+                    .map_err(Error::from_platform)?;
+
+                convert_value(value)
+            })
+            .await
+            .copied()
+    }
+
+    async fn get_settings_apperance(&self, key: &str) -> Result<OwnedValue, Error> {
+        // Older portals only provide the deprecated read method, the new one is tried first when
+        // the interface version can't be read
+        let mut legacy = match self.get_settings_version().await {
+            Ok(version) => version < READ_METHOD_VERSION,
+            Err(_) => false,
+        };
+
+        let response = if legacy {
+            self.read_setting(LEGACY_READ_METHOD, key).await
+        } else {
+            match self.read_setting(READ_METHOD, key).await {
+                Err(zbus::Error::MethodError(name, _, _))
+                    if name.as_str() == DBUS_UNKNOWN_METHOD =>
+                {
+                    legacy = true;
+                    self.read_setting(LEGACY_READ_METHOD, key).await
+                }
+                response => response,
+            }
+        }?;
+
+        // As the result is a variant, convert it to a value
+        let value = response
+            .body()
+            .deserialize::<OwnedValue>()
+            .map_err(Error::from_platform)?;
+
+        decode_read_reply(value, legacy)
+    }
+
+    /// Call a method to read a settings appearance
+    async fn read_setting(&self, method: &str, key: &str) -> zbus::Result<Message> {
+        let conn = self.conn.as_ref().ok_or(zbus::Error::Unsupported)?;
+
+        conn.call_method(
+            Some(DESKTOP_PORTAL_DEST),
+            DESKTOP_PORTAL_PATH,
+            Some(SETTINGS_INTERFACE),
+            method,
+            &(APPERANCE_NAMESPACE, key),
+        )
+        .await
+    }
+
+    async fn get_all_settings(&self, namespaces: &[&str]) -> Result<SettingsNamespaces, Error> {
+        // Call method to read whole namespaces at once
+        let response = self
+            .conn()?
+            .call_method(
+                Some(DESKTOP_PORTAL_DEST),
+                DESKTOP_PORTAL_PATH,
+                Some(SETTINGS_INTERFACE),
+                READ_ALL_METHOD,
+                &(namespaces,),
+            )
+            .await?;
+
+        response
+            .body()
+            .deserialize::<SettingsNamespaces>()
+            .map_err(Error::from_platform)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_u32(value: Value<'_>, legacy: bool) -> u32 {
+        let value = value.try_to_owned().unwrap();
+        decode_read_reply(value, legacy)
+            .unwrap()
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn read_one_reply() {
+        // `ReadOne` replies with the value (signature `v`)
+        assert_eq!(decode_u32(Value::U32(1), false), 1);
+    }
+
+    #[test]
+    fn legacy_read_reply() {
+        // `Read` replies with the value wrapped in another variant (signature `v` in `v`)
+        let value = Value::Value(Box::new(Value::U32(2)));
+        assert_eq!(decode_u32(value, true), 2);
+    }
+
+    #[test]
+    fn legacy_read_plain_reply() {
+        // Some implementations of `Read` don't add the extra variant
+        assert_eq!(decode_u32(Value::U32(2), true), 2);
+    }
+
+    #[test]
+    fn read_one_nested_reply() {
+        // The value of `ReadOne` is never unwrapped
+        let value = Value::Value(Box::new(Value::U32(1)))
+            .try_to_owned()
+            .unwrap();
+        let value = decode_read_reply(value, false).unwrap();
+        assert!(matches!(&*value, Value::Value(_)));
+    }
+
+    fn gtk_providers(settings: &'static str) -> LazyCell<Providers, impl FnOnce() -> Providers> {
+        LazyCell::new(move || {
+            let settings = gtksettings::GtkSettings::new(crate::ini::Ini::parse(settings), None);
+            Providers::new([Box::new(settings) as Box<dyn Provider>])
+        })
+    }
+
+    #[test]
+    fn no_preference_uses_theme_name() {
+        // GTK portal on Xfce, LXDE or window managers has no preference
+        let providers = gtk_providers("[Settings]\ngtk-theme-name=Adwaita-dark\n");
+        let scheme = resolve_scheme(Ok(None), &providers).unwrap();
+        assert_eq!(scheme, Some(ThemeScheme::Dark));
+
+        let providers = gtk_providers("[Settings]\ngtk-theme-name=Adwaita\n");
+        let scheme = resolve_scheme(Ok(None), &providers).unwrap();
+        assert_eq!(scheme, Some(ThemeScheme::Light));
+    }
+
+    #[test]
+    fn no_preference_without_files() {
+        let providers = gtk_providers("");
+        let scheme = resolve_scheme(Ok(None), &providers).unwrap();
+        assert_eq!(scheme, None);
+    }
+
+    #[test]
+    fn portal_preference_is_kept() {
+        let providers = gtk_providers("[Settings]\ngtk-theme-name=Adwaita-dark\n");
+        let scheme = resolve_scheme(Ok(Some(ThemeScheme::Light)), &providers).unwrap();
+        assert_eq!(scheme, Some(ThemeScheme::Light));
+    }
+
+    fn snapshot(accent: Result<ThemeColor, Error>) -> Snapshot {
+        let settings = ThemeSettings {
+            kind: Ok(ThemeKind::Gtk),
+            scheme: Ok(Some(ThemeScheme::Dark)),
+            contrast: Ok(ThemeContrast::Normal),
+            accent,
+            accent_shades: None,
+            theme: None,
+        };
+        Snapshot::new((settings, Ok(ThemeMotion::Normal)), Err(Error::Unsupported))
+    }
+
+    #[test]
+    fn snapshot_accent_changes() {
+        let blue = ThemeColor::from_rgb8(53, 132, 228);
+        let red = ThemeColor::from_rgb8(230, 45, 66);
+        let mut last = snapshot(Ok(blue));
+
+        assert_eq!(last.update(snapshot(Ok(blue))), []);
+        assert_eq!(
+            last.update(snapshot(Ok(red))),
+            [Some(ThemeChange::Accent(Some(red)))]
+        );
+
+        // Read errors keep the last accent, instead of reporting it as removed
+        let error = Error::from_platform(std::io::Error::other("portal timed out"));
+        assert_eq!(last.update(snapshot(Err(error))), []);
+        assert_eq!(last.update(snapshot(Ok(red))), []);
+
+        // Accent is no longer configured
+        assert_eq!(
+            last.update(snapshot(Err(Error::Unavailable))),
+            [Some(ThemeChange::Accent(None))]
+        );
+        assert_eq!(last.update(snapshot(Err(Error::Unsupported))), []);
+    }
+
+    #[test]
+    fn snapshot_portal_changes() {
+        let blue = ThemeColor::from_rgb8(53, 132, 228);
+        let mut last = snapshot(Ok(blue));
+
+        let next = last.with_change(ThemeChange::Accent(None));
+        assert_eq!(last.update(next), [Some(ThemeChange::Accent(None))]);
+
+        let next = last.with_change(ThemeChange::Scheme(Some(ThemeScheme::Dark)));
+        assert_eq!(last.update(next), []);
+    }
+}
@@ -0,0 +1,128 @@
+//! Settings providers reading desktop configuration files
+//!
+//! Used when the desktop portal can't provide a setting.
+use super::{
+    desktop, gsettings::GSettings, gtksettings::GtkSettings, kdeglobals::KdeGlobals,
+    xresources::Xresources,
+};
+use crate::{
+    error::Error, Desktop, ThemeColor, ThemeContrast, ThemeFonts, ThemeMotion, ThemeScheme,
+};
+
+/// Configuration file providing theme settings
+///
+/// Settings not stored in the file are unsupported by default.
+pub trait Provider {
+    /// Explicit scheme preference (e.g. a "prefer dark" setting)
+    fn theme_scheme(&self) -> Result<ThemeScheme, Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Scheme guessed from the theme in use, when nothing tells the preference.
+    fn theme_scheme_hint(&self) -> Result<ThemeScheme, Error> {
+        self.theme_scheme()
+    }
+
+    fn theme_contrast(&self) -> Result<ThemeContrast, Error> {
+        Err(Error::Unsupported)
+    }
+
+    fn theme_accent(&self) -> Result<ThemeColor, Error> {
+        Err(Error::Unsupported)
+    }
+
+    fn theme_motion(&self) -> Result<ThemeMotion, Error> {
+        Err(Error::Unsupported)
+    }
+
+    fn theme_fonts(&self) -> Result<ThemeFonts, Error> {
+        Err(Error::Unsupported)
+    }
+
+    fn text_scale(&self) -> Result<f32, Error> {
+        Err(Error::Unsupported)
+    }
+
+    fn cursor_size(&self) -> Result<u32, Error> {
+        Err(Error::Unsupported)
+    }
+
+    fn gtk_theme(&self) -> Result<String, Error> {
+        Err(Error::Unsupported)
+    }
+}
+
+/// Available providers, in order of preference
+pub struct Providers {
+    providers: Vec<Box<dyn Provider>>,
+}
+
+impl Providers {
+    /// Load the providers that have a configuration file.
+    pub fn load() -> Self {
+        let gsettings = GSettings::load().map(|provider| Box::new(provider) as Box<dyn Provider>);
+        let gtksettings =
+            GtkSettings::load().map(|provider| Box::new(provider) as Box<dyn Provider>);
+        let kdeglobals = KdeGlobals::load().map(|provider| Box::new(provider) as Box<dyn Provider>);
+        let xresources = Xresources::load().map(|provider| Box::new(provider) as Box<dyn Provider>);
+
+        // Files can exist at the same time, prefer the ones from the running desktop.
+        // GTK settings are only a hint, so they come after GSettings.
+        // X resources are set by any X11 session, so they come last.
+        let providers = if desktop::from_env() == Some(Desktop::Kde) {
+            [kdeglobals, gsettings, gtksettings, xresources]
+        } else {
+            [gsettings, gtksettings, kdeglobals, xresources]
+        };
+
+        Self::new(providers.into_iter().flatten())
+    }
+
+    /// Use the given providers, in order of preference.
+    pub fn new(providers: impl IntoIterator<Item = Box<dyn Provider>>) -> Self {
+        Self {
+            providers: providers.into_iter().collect(),
+        }
+    }
+
+    /// Get the GTK theme name, only the files that can have it are read.
+    pub fn gtk_theme() -> Result<String, Error> {
+        GSettings::load()
+            .ok_or(Error::Unsupported)
+            .and_then(|provider| provider.gtk_theme())
+            .or_else(|_| {
+                GtkSettings::load()
+                    .ok_or(Error::Unsupported)
+                    .and_then(|provider| provider.gtk_theme())
+            })
+    }
+
+    /// Get the paths of every file read by the providers (only needed to watch them).
+    #[cfg(target_os = "linux")]
+    pub fn paths() -> Vec<std::path::PathBuf> {
+        let mut paths: Vec<_> = GSettings::path().into_iter().collect();
+        paths.extend(GtkSettings::paths());
+        paths.extend(KdeGlobals::paths());
+        paths.extend(Xresources::paths());
+        paths
+    }
+
+    /// Read a setting from the first provider that has it.
+    pub fn get<T>(&self, read: impl Fn(&dyn Provider) -> Result<T, Error>) -> Result<T, Error> {
+        let mut error = Error::Unsupported;
+
+        for provider in &self.providers {
+            match read(provider.as_ref()) {
+                Ok(value) => return Ok(value),
+                // Keep the most relevant error (anything is better than unsupported)
+                Err(err) => {
+                    if matches!(error, Error::Unsupported) {
+                        error = err;
+                    }
+                }
+            }
+        }
+
+        Err(error)
+    }
+}
@@ -0,0 +1,159 @@
+//! Configuration file watcher, based on inotify
+//!
+//! Parent directories are watched instead of the files, as most programs (dconf, `kwriteconfig`,
+//! editors) save by writing a temporary file and renaming it over the original one.
+use async_io::Async;
+use async_stream::stream;
+use futures_core::stream::Stream;
+use rustix::{
+    fd::OwnedFd,
+    fs::inotify::{self, CreateFlags, ReadFlags, WatchFlags},
+    io::Errno,
+};
+use std::{
+    collections::HashMap,
+    ffi::{OsStr, OsString},
+    mem::MaybeUninit,
+    os::unix::ffi::OsStrExt,
+    path::PathBuf,
+};
+
+/// Events meaning a file was saved (in place or renamed over) or a directory was created
+const WATCH_FLAGS: WatchFlags = WatchFlags::CLOSE_WRITE
+    .union(WatchFlags::MOVED_TO)
+    .union(WatchFlags::CREATE)
+    .union(WatchFlags::ONLYDIR);
+
+/// Event buffer size, enough for multiple events with file names
+const BUFFER_SIZE: usize = 4096;
+
+/// Watched directory and the files of interest in it
+struct Directory {
+    path: PathBuf,
+    files: Vec<OsString>,
+    watch: Option<i32>,
+}
+
+/// Watch files for changes.
+///
+/// Watches are added before returning, so saves made after the call are never missed. The stream
+/// yields every time one of the files is saved, it ends if inotify is not available.
+pub fn watch(paths: Vec<PathBuf>) -> impl Stream<Item = ()> {
+    let watcher = init(paths);
+
+    stream! {
+        let Some((fd, mut directories)) = watcher else {
+            return;
+        };
+
+        let mut buffer = [MaybeUninit::uninit(); BUFFER_SIZE];
+        loop {
+            if fd.readable().await.is_err() {
+                return;
+            }
+
+            let mut changed = false;
+            let mut created_directory = false;
+
+            // Read every pending event, a save can generate more than one
+            let mut reader = inotify::Reader::new(fd.get_ref(), &mut buffer);
+            loop {
+                let event = match reader.next() {
+                    Ok(event) => event,
+                    Err(Errno::WOULDBLOCK) | Err(Errno::INTR) => break,
+                    Err(_) => return,
+                };
+
+                // Missed events can't be known, so assume something changed
+                if event.events().contains(ReadFlags::QUEUE_OVERFLOW) {
+                    changed = true;
+                    continue;
+                }
+
+                // Directory was removed, it can be watched again if created later
+                if event.events().contains(ReadFlags::IGNORED) {
+                    for directory in &mut directories {
+                        if directory.watch == Some(event.wd()) {
+                            directory.watch = None;
+                        }
+                    }
+                    continue;
+                }
+
+                if event.events().contains(ReadFlags::ISDIR) {
+                    created_directory = true;
+                    continue;
+                }
+
+                let Some(name) = event.file_name() else {
+                    continue;
+                };
+                let name = OsStr::from_bytes(name.to_bytes());
+
+                changed |= directories.iter().any(|directory| {
+                    directory.watch == Some(event.wd())
+                        && directory.files.iter().any(|file| file == name)
+                });
+            }
+
+            // Directories that didn't exist may have been created (files could already be there)
+            if created_directory {
+                changed |= add_watches(fd.get_ref(), &mut directories);
+            }
+
+            if changed {
+                yield ();
+            }
+        }
+    }
+}
+
+/// Create the inotify instance and watch the directories of the files
+fn init(paths: Vec<PathBuf>) -> Option<(Async<OwnedFd>, Vec<Directory>)> {
+    let fd = inotify::init(CreateFlags::CLOEXEC | CreateFlags::NONBLOCK).ok()?;
+    let fd = Async::new(fd).ok()?;
+
+    let mut directories = group_by_directory(paths);
+    add_watches(fd.get_ref(), &mut directories);
+    Some((fd, directories))
+}
+
+/// Group files by their parent directory
+fn group_by_directory(paths: Vec<PathBuf>) -> Vec<Directory> {
+    let mut directories: HashMap<PathBuf, Vec<OsString>> = HashMap::new();
+
+    for path in paths {
+        if let (Some(parent), Some(name)) = (path.parent(), path.file_name()) {
+            directories
+                .entry(parent.to_path_buf())
+                .or_default()
+                .push(name.to_os_string());
+        }
+    }
+
+    directories
+        .into_iter()
+        .map(|(path, files)| Directory {
+            path,
+            files,
+            watch: None,
+        })
+        .collect()
+}
+
+/// Watch the directories that aren't watched yet (ignoring the ones that don't exist).
+///
+/// Returns if any new directory is now watched.
+fn add_watches(fd: &OwnedFd, directories: &mut [Directory]) -> bool {
+    let mut added = false;
+
+    for directory in directories
+        .iter_mut()
+        .filter(|directory| directory.watch.is_none())
+    {
+        directory.watch = inotify::add_watch(fd, &directory.path, WATCH_FLAGS).ok();
+        added |= directory.watch.is_some();
+    }
+
+    added
+}
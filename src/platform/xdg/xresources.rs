@@ -0,0 +1,85 @@
+//! X resources reader (`~/.Xdefaults` and `~/.Xresources`)
+//!
+//! Only the files are read, the resource database of a running X server is not queried.
+use std::{collections::HashMap, env, fs, path::PathBuf};
+
+use super::provider::Provider;
+use crate::error::Error;
+
+/// Resource files, in increasing order of preference
+const RESOURCE_FILES: &[&str] = &[".Xdefaults", ".Xresources"];
+
+const DPI_RESOURCE: &str = "Xft.dpi";
+const CURSOR_SIZE_RESOURCE: &str = "Xcursor.size";
+
+/// Parse resource lines (`name: value`) into the map, later ones override earlier ones
+fn parse(content: &str, resources: &mut HashMap<String, String>) {
+    for line in content.lines() {
+        let line = line.trim();
+
+        // Skip comments and preprocessor directives (used by `xrdb`)
+        if line.is_empty() || line.starts_with('!') || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            // Loose bindings are treated like tight ones (`Xft*dpi` -> `Xft.dpi`)
+            let name = name.trim().replace('*', ".");
+            resources.insert(
+                name.trim_start_matches('.').to_string(),
+                value.trim().to_string(),
+            );
+        }
+    }
+}
+
+/// User X resources
+pub struct Xresources {
+    resources: HashMap<String, String>,
+}
+
+impl Xresources {
+    /// Load and merge the resource files, returns `None` if there's none.
+    pub fn load() -> Option<Self> {
+        let mut resources = HashMap::new();
+        let mut found = false;
+
+        for path in Self::paths() {
+            if let Ok(content) = fs::read_to_string(path) {
+                parse(&content, &mut resources);
+                found = true;
+            }
+        }
+
+        found.then_some(Self { resources })
+    }
+
+    /// Get the paths of the resource files, in increasing order of preference.
+    pub fn paths() -> Vec<PathBuf> {
+        let Some(home) = env::var_os("HOME").map(PathBuf::from) else {
+            return Vec::new();
+        };
+
+        RESOURCE_FILES.iter().map(|file| home.join(file)).collect()
+    }
+
+    fn get<T: std::str::FromStr>(&self, name: &str) -> Option<T> {
+        self.resources
+            .get(name)
+            .and_then(|value| value.parse().ok())
+    }
+}
+
+impl Provider for Xresources {
+    fn text_scale(&self) -> Result<f32, Error> {
+        self.get(DPI_RESOURCE)
+            .and_then(super::dpi_scale)
+            .ok_or(Error::Unavailable)
+    }
+
+    fn cursor_size(&self) -> Result<u32, Error> {
+        self.get(CURSOR_SIZE_RESOURCE)
+            .filter(|size| *size > 0)
+            .ok_or(Error::Unavailable)
+    }
+}
@@ -0,0 +1,589 @@
+//! Theme colors
+use std::{
+    fmt::{self, Debug, Display},
+    str::FromStr,
+};
+
+use crate::{error::Error, Hsl, Oklab, Oklch};
+
+/// CSS named colors (lowercase), as packed `0xRRGGBB` values
+///
+/// Source: https://www.w3.org/TR/css-color-4/#named-colors
+const NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xF0F8FF),
+    ("antiquewhite", 0xFAEBD7),
+    ("aqua", 0x00FFFF),
+    ("aquamarine", 0x7FFFD4),
+    ("azure", 0xF0FFFF),
+    ("beige", 0xF5F5DC),
+    ("bisque", 0xFFE4C4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xFFEBCD),
+    ("blue", 0x0000FF),
+    ("blueviolet", 0x8A2BE2),
+    ("brown", 0xA52A2A),
+    ("burlywood", 0xDEB887),
+    ("cadetblue", 0x5F9EA0),
+    ("chartreuse", 0x7FFF00),
+    ("chocolate", 0xD2691E),
+    ("coral", 0xFF7F50),
+    ("cornflowerblue", 0x6495ED),
+    ("cornsilk", 0xFFF8DC),
+    ("crimson", 0xDC143C),
+    ("cyan", 0x00FFFF),
+    ("darkblue", 0x00008B),
+    ("darkcyan", 0x008B8B),
+    ("darkgoldenrod", 0xB8860B),
+    ("darkgray", 0xA9A9A9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xA9A9A9),
+    ("darkkhaki", 0xBDB76B),
+    ("darkmagenta", 0x8B008B),
+    ("darkolivegreen", 0x556B2F),
+    ("darkorange", 0xFF8C00),
+    ("darkorchid", 0x9932CC),
+    ("darkred", 0x8B0000),
+    ("darksalmon", 0xE9967A),
+    ("darkseagreen", 0x8FBC8F),
+    ("darkslateblue", 0x483D8B),
+    ("darkslategray", 0x2F4F4F),
+    ("darkslategrey", 0x2F4F4F),
+    ("darkturquoise", 0x00CED1),
+    ("darkviolet", 0x9400D3),
+    ("deeppink", 0xFF1493),
+    ("deepskyblue", 0x00BFFF),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1E90FF),
+    ("firebrick", 0xB22222),
+    ("floralwhite", 0xFFFAF0),
+    ("forestgreen", 0x228B22),
+    ("fuchsia", 0xFF00FF),
+    ("gainsboro", 0xDCDCDC),
+    ("ghostwhite", 0xF8F8FF),
+    ("gold", 0xFFD700),
+    ("goldenrod", 0xDAA520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xADFF2F),
+    ("grey", 0x808080),
+    ("honeydew", 0xF0FFF0),
+    ("hotpink", 0xFF69B4),
+    ("indianred", 0xCD5C5C),
+    ("indigo", 0x4B0082),
+    ("ivory", 0xFFFFF0),
+    ("khaki", 0xF0E68C),
+    ("lavender", 0xE6E6FA),
+    ("lavenderblush", 0xFFF0F5),
+    ("lawngreen", 0x7CFC00),
+    ("lemonchiffon", 0xFFFACD),
+    ("lightblue", 0xADD8E6),
+    ("lightcoral", 0xF08080),
+    ("lightcyan", 0xE0FFFF),
+    ("lightgoldenrodyellow", 0xFAFAD2),
+    ("lightgray", 0xD3D3D3),
+    ("lightgreen", 0x90EE90),
+    ("lightgrey", 0xD3D3D3),
+    ("lightpink", 0xFFB6C1),
+    ("lightsalmon", 0xFFA07A),
+    ("lightseagreen", 0x20B2AA),
+    ("lightskyblue", 0x87CEFA),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xB0C4DE),
+    ("lightyellow", 0xFFFFE0),
+    ("lime", 0x00FF00),
+    ("limegreen", 0x32CD32),
+    ("linen", 0xFAF0E6),
+    ("magenta", 0xFF00FF),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66CDAA),
+    ("mediumblue", 0x0000CD),
+    ("mediumorchid", 0xBA55D3),
+    ("mediumpurple", 0x9370DB),
+    ("mediumseagreen", 0x3CB371),
+    ("mediumslateblue", 0x7B68EE),
+    ("mediumspringgreen", 0x00FA9A),
+    ("mediumturquoise", 0x48D1CC),
+    ("mediumvioletred", 0xC71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xF5FFFA),
+    ("mistyrose", 0xFFE4E1),
+    ("moccasin", 0xFFE4B5),
+    ("navajowhite", 0xFFDEAD),
+    ("navy", 0x000080),
+    ("oldlace", 0xFDF5E6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6B8E23),
+    ("orange", 0xFFA500),
+    ("orangered", 0xFF4500),
+    ("orchid", 0xDA70D6),
+    ("palegoldenrod", 0xEEE8AA),
+    ("palegreen", 0x98FB98),
+    ("paleturquoise", 0xAFEEEE),
+    ("palevioletred", 0xDB7093),
+    ("papayawhip", 0xFFEFD5),
+    ("peachpuff", 0xFFDAB9),
+    ("peru", 0xCD853F),
+    ("pink", 0xFFC0CB),
+    ("plum", 0xDDA0DD),
+    ("powderblue", 0xB0E0E6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xFF0000),
+    ("rosybrown", 0xBC8F8F),
+    ("royalblue", 0x4169E1),
+    ("saddlebrown", 0x8B4513),
+    ("salmon", 0xFA8072),
+    ("sandybrown", 0xF4A460),
+    ("seagreen", 0x2E8B57),
+    ("seashell", 0xFFF5EE),
+    ("sienna", 0xA0522D),
+    ("silver", 0xC0C0C0),
+    ("skyblue", 0x87CEEB),
+    ("slateblue", 0x6A5ACD),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xFFFAFA),
+    ("springgreen", 0x00FF7F),
+    ("steelblue", 0x4682B4),
+    ("tan", 0xD2B48C),
+    ("teal", 0x008080),
+    ("thistle", 0xD8BFD8),
+    ("tomato", 0xFF6347),
+    ("turquoise", 0x40E0D0),
+    ("violet", 0xEE82EE),
+    ("wheat", 0xF5DEB3),
+    ("white", 0xFFFFFF),
+    ("whitesmoke", 0xF5F5F5),
+    ("yellow", 0xFFFF00),
+    ("yellowgreen", 0x9ACD32),
+];
+
+/// Convert a color component to its 8-bit value (rounded to the closest one)
+fn component_to_u8(component: f32) -> u8 {
+    (component.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Parse a number, or a percentage of the given scale (e.g. "50%" is half of it)
+fn parse_number(value: &str, percent_scale: f32) -> Option<f32> {
+    match value.strip_suffix('%') {
+        Some(percent) => Some(percent.trim().parse::<f32>().ok()? / 100.0 * percent_scale),
+        None => value.parse().ok(),
+    }
+    .filter(|number| number.is_finite())
+}
+
+/// Parse a hex color (`#RGB`, `#RGBA`, `#RRGGBB` or `#RRGGBBAA`)
+fn parse_hex(value: &str) -> Option<ThemeColor> {
+    let hex = value.strip_prefix('#')?;
+    if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let digits = |range: std::ops::Range<usize>| u8::from_str_radix(&hex[range], 16).ok();
+
+    match hex.len() {
+        // Short forms repeat each digit (`#F80` is `#FF8800`)
+        3 | 4 => {
+            let digit = |index: usize| digits(index..index + 1).map(|digit| digit * 0x11);
+            let alpha = if hex.len() == 4 { digit(3)? } else { u8::MAX };
+            Some(ThemeColor::from_rgba8(
+                digit(0)?,
+                digit(1)?,
+                digit(2)?,
+                alpha,
+            ))
+        }
+        6 | 8 => {
+            let alpha = if hex.len() == 8 {
+                digits(6..8)?
+            } else {
+                u8::MAX
+            };
+            Some(ThemeColor::from_rgba8(
+                digits(0..2)?,
+                digits(2..4)?,
+                digits(4..6)?,
+                alpha,
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Parse a CSS color function (`rgb()`, `rgba()`, `hsl()` or `hsla()`).
+///
+/// Both the legacy (comma separated) and modern (space separated, with `/ alpha`) syntaxes are
+/// supported.
+fn parse_function(value: &str) -> Option<ThemeColor> {
+    let (name, arguments) = value.strip_suffix(')')?.split_once('(')?;
+
+    let (components, alpha): (Vec<&str>, _) = if arguments.contains(',') {
+        let mut components: Vec<&str> = arguments.split(',').map(str::trim).collect();
+        let alpha = (components.len() == 4).then(|| components.pop()).flatten();
+        (components, alpha)
+    } else {
+        match arguments.split_once('/') {
+            Some((components, alpha)) => (components.split_whitespace().collect(), Some(alpha)),
+            None => (arguments.split_whitespace().collect(), None),
+        }
+    };
+
+    let [first, second, third] = components[..] else {
+        return None;
+    };
+
+    let alpha = match alpha {
+        Some(alpha) => parse_number(alpha.trim(), 1.0)?.clamp(0.0, 1.0),
+        None => 1.0,
+    };
+
+    match name.trim().to_ascii_lowercase().as_str() {
+        "rgb" | "rgba" => {
+            let component = |value| Some(parse_number(value, 255.0)?.clamp(0.0, 255.0) / 255.0);
+            Some(ThemeColor::from_rgba(
+                component(first)?,
+                component(second)?,
+                component(third)?,
+                alpha,
+            ))
+        }
+        "hsl" | "hsla" => {
+            let hue = parse_number(first.strip_suffix("deg").unwrap_or(first), 360.0)?;
+            let component = |value| Some(parse_number(value, 100.0)?.clamp(0.0, 100.0) / 100.0);
+            Some(ThemeColor::from_hsl(Hsl {
+                hue,
+                saturation: component(second)?,
+                lightness: component(third)?,
+                alpha,
+            }))
+        }
+        _ => None,
+    }
+}
+
+/// Parse a CSS named color (or `transparent`)
+fn parse_named(value: &str) -> Option<ThemeColor> {
+    let value = value.to_ascii_lowercase();
+    if value == "transparent" {
+        return Some(ThemeColor::TRANSPARENT);
+    }
+
+    NAMED_COLORS
+        .iter()
+        .find(|(name, _)| *name == value)
+        .map(|(_, rgb)| ThemeColor::from_rgba_u32(rgb << 8 | 0xFF))
+}
+
+/// Theme color
+///
+/// Colors can be parsed from CSS strings (hex, `rgb()`, `hsl()` and named colors), and are
+/// displayed as `#RRGGBB` (or `#RRGGBBAA` if translucent). With the `serde` feature, colors are
+/// (de)serialized as these strings.
+#[derive(PartialEq, Clone, Copy)]
+pub struct ThemeColor {
+    /// Red component (0.0 - 1.0)
+    pub red: f32,
+    /// Green component (0.0 - 1.0)
+    pub green: f32,
+    /// Blue component (0.0 - 1.0)
+    pub blue: f32,
+    /// Alpha component (0.0 is transparent, 1.0 is opaque)
+    pub alpha: f32,
+}
+
+impl Debug for ThemeColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for ThemeColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [red, green, blue, alpha] = self.to_rgba8();
+
+        write!(f, "#{red:02X}{green:02X}{blue:02X}")?;
+        if alpha != u8::MAX {
+            write!(f, "{alpha:02X}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for ThemeColor {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Error> {
+        let value = value.trim();
+
+        parse_hex(value)
+            .or_else(|| parse_function(value))
+            .or_else(|| parse_named(value))
+            .ok_or_else(|| Error::Parse(format!("invalid color \"{value}\"")))
+    }
+}
+
+impl ThemeColor {
+    /// White color (#FFFFFF)
+    pub const WHITE: Self = Self::from_rgb8(255, 255, 255);
+
+    /// Black color (#000000)
+    pub const BLACK: Self = Self::from_rgb8(0, 0, 0);
+
+    /// Transparent color (#00000000)
+    pub const TRANSPARENT: Self = Self::from_rgba8(0, 0, 0, 0);
+
+    /// Create a color from its components (0.0 - 1.0).
+    pub const fn from_rgba(red: f32, green: f32, blue: f32, alpha: f32) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
+    /// Create an opaque color from RGB 8-bit values.
+    pub const fn from_rgb8(red: u8, green: u8, blue: u8) -> Self {
+        Self::from_rgba8(red, green, blue, u8::MAX)
+    }
+
+    /// Create a color from RGBA 8-bit values.
+    pub const fn from_rgba8(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Self {
+            red: red as f32 / 255.0,
+            green: green as f32 / 255.0,
+            blue: blue as f32 / 255.0,
+            alpha: alpha as f32 / 255.0,
+        }
+    }
+
+    /// Create a color from a packed `0xRRGGBBAA` value.
+    pub const fn from_rgba_u32(rgba: u32) -> Self {
+        let [red, green, blue, alpha] = rgba.to_be_bytes();
+        Self::from_rgba8(red, green, blue, alpha)
+    }
+
+    /// Get the RGBA 8-bit values (rounded to the closest ones).
+    pub fn to_rgba8(self) -> [u8; 4] {
+        [
+            component_to_u8(self.red),
+            component_to_u8(self.green),
+            component_to_u8(self.blue),
+            component_to_u8(self.alpha),
+        ]
+    }
+
+    /// Get the packed `0xRRGGBBAA` value (rounded to the closest one).
+    pub fn to_rgba_u32(self) -> u32 {
+        u32::from_be_bytes(self.to_rgba8())
+    }
+
+    /// Get the same color with another alpha value.
+    pub const fn with_alpha(self, alpha: f32) -> Self {
+        Self { alpha, ..self }
+    }
+
+    /// Mix with another color in sRGB (0.0 keeps this color, 1.0 gives the other one).
+    ///
+    /// See [`ThemeColor::mix_oklab`] for a perceptually even mix.
+    pub const fn mix(self, other: ThemeColor, amount: f32) -> Self {
+        let amount = amount.clamp(0.0, 1.0);
+
+        Self {
+            red: self.red + (other.red - self.red) * amount,
+            green: self.green + (other.green - self.green) * amount,
+            blue: self.blue + (other.blue - self.blue) * amount,
+            alpha: self.alpha + (other.alpha - self.alpha) * amount,
+        }
+    }
+
+    /// Mix with another color in OKLab (0.0 keeps this color, 1.0 gives the other one).
+    ///
+    /// Unlike [`ThemeColor::mix`], intermediate colors don't get muddy and their lightness changes
+    /// evenly.
+    pub fn mix_oklab(self, other: ThemeColor, amount: f32) -> Self {
+        let amount = amount.clamp(0.0, 1.0);
+        let from = self.to_oklab();
+        let to = other.to_oklab();
+        let mix = |from: f32, to: f32| from + (to - from) * amount;
+
+        let mixed = Self::from_oklab(Oklab {
+            lightness: mix(from.lightness, to.lightness),
+            a: mix(from.a, to.a),
+            b: mix(from.b, to.b),
+            alpha: mix(from.alpha, to.alpha),
+        });
+
+        // Mixing two sRGB colors can only leave the gamut by rounding errors
+        Self::from_rgba(
+            mixed.red.clamp(0.0, 1.0),
+            mixed.green.clamp(0.0, 1.0),
+            mixed.blue.clamp(0.0, 1.0),
+            mixed.alpha,
+        )
+    }
+
+    /// Increase the perceptual lightness (OKLCH lightness, 0.0 - 1.0) by the given amount.
+    pub fn lighten(self, amount: f32) -> Self {
+        let color = self.to_oklch();
+        Self::from_oklch_mapped(Oklch {
+            lightness: color.lightness + amount,
+            ..color
+        })
+    }
+
+    /// Decrease the perceptual lightness (OKLCH lightness, 0.0 - 1.0) by the given amount.
+    pub fn darken(self, amount: f32) -> Self {
+        self.lighten(-amount)
+    }
+
+    /// Scale the chroma (OKLCH) by `1.0 + amount`, a negative amount desaturates the color.
+    ///
+    /// Chroma is limited to what sRGB can show for the color's lightness and hue.
+    pub fn saturate(self, amount: f32) -> Self {
+        let color = self.to_oklch();
+        Self::from_oklch_mapped(Oklch {
+            chroma: (color.chroma * (1.0 + amount)).max(0.0),
+            ..color
+        })
+    }
+
+    /// Decrease the chroma (OKLCH), 1.0 gives a gray of the same lightness.
+    pub fn desaturate(self, amount: f32) -> Self {
+        self.saturate(-amount)
+    }
+
+    /// Composite this (translucent) color over a background (source over, in sRGB).
+    ///
+    /// The result is opaque if the background is.
+    pub fn composite_over(self, background: ThemeColor) -> Self {
+        let alpha = self.alpha + background.alpha * (1.0 - self.alpha);
+        if alpha <= 0.0 {
+            return Self::TRANSPARENT;
+        }
+
+        let blend = |foreground: f32, background_component: f32| {
+            (foreground * self.alpha + background_component * background.alpha * (1.0 - self.alpha))
+                / alpha
+        };
+
+        Self::from_rgba(
+            blend(self.red, background.red),
+            blend(self.green, background.green),
+            blend(self.blue, background.blue),
+            alpha,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str) -> String {
+        value.parse::<ThemeColor>().unwrap().to_string()
+    }
+
+    #[test]
+    fn hex_colors() {
+        assert_eq!(parse("#F80"), "#FF8800");
+        assert_eq!(parse("#f808"), "#FF880088");
+        assert_eq!(parse("#3daee9"), "#3DAEE9");
+        assert_eq!(parse("#3DAEE980"), "#3DAEE980");
+        assert_eq!(parse("  #3DAEE9\n"), "#3DAEE9");
+        // Opaque alpha is omitted
+        assert_eq!(parse("#3DAEE9FF"), "#3DAEE9");
+    }
+
+    #[test]
+    fn function_colors() {
+        assert_eq!(parse("rgb(61, 174, 233)"), "#3DAEE9");
+        assert_eq!(parse("rgba(61, 174, 233, 0.5)"), "#3DAEE980");
+        assert_eq!(parse("rgb(61 174 233 / 50%)"), "#3DAEE980");
+        assert_eq!(parse("rgb(100%, 50%, 0%)"), "#FF8000");
+        assert_eq!(parse("RGB(0,0,0)"), "#000000");
+        assert_eq!(parse("hsl(120, 100%, 25%)"), "#008000");
+        assert_eq!(parse("hsl(120deg 100% 25% / 0.2)"), "#00800033");
+        assert_eq!(parse("hsla(0, 0%, 100%, 1)"), "#FFFFFF");
+        // Out of range values are clamped, like CSS does
+        assert_eq!(parse("rgb(300, 0, 0)"), "#FF0000");
+        assert_eq!(parse("rgba(0, 0, 0, 2)"), "#000000");
+    }
+
+    #[test]
+    fn named_colors() {
+        assert_eq!(parse("rebeccapurple"), "#663399");
+        assert_eq!(parse("RebeccaPurple"), "#663399");
+        assert_eq!(parse("white"), "#FFFFFF");
+        assert_eq!(parse("transparent"), "#00000000");
+        assert_eq!(
+            "transparent".parse::<ThemeColor>().unwrap(),
+            ThemeColor::TRANSPARENT
+        );
+    }
+
+    #[test]
+    fn invalid_colors() {
+        let invalid = [
+            "",
+            "#",
+            "#12",
+            "#12345",
+            "#1234567",
+            "#GGGGGG",
+            "#+1234F",
+            "3DAEE9",
+            "rgb(1, 2)",
+            "rgb(1, 2, 3, 4, 5)",
+            "rgb(a, b, c)",
+            "rgb(1, 2, 3",
+            "rgb(NaN, 0, 0)",
+            "hsl()",
+            "cmyk(0, 0, 0)",
+            "notacolor",
+        ];
+
+        for value in invalid {
+            let error = value.parse::<ThemeColor>().unwrap_err();
+            assert!(matches!(error, Error::Parse(_)), "{value}");
+        }
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let colors = [
+            ThemeColor::from_rgb8(0, 0, 0),
+            ThemeColor::from_rgb8(255, 255, 255),
+            ThemeColor::from_rgba8(18, 52, 86, 120),
+            ThemeColor::from_rgba8(1, 2, 3, 0),
+        ];
+
+        for color in colors {
+            assert_eq!(color.to_string().parse::<ThemeColor>().unwrap(), color);
+        }
+    }
+
+    #[test]
+    fn rounding() {
+        // Components are rounded to the closest 8-bit value, and clamped
+        let color = ThemeColor::from_rgba(0.5, 0.499, 1.5, -0.5);
+        assert_eq!(color.to_rgba8(), [128, 127, 255, 0]);
+        assert_eq!(
+            ThemeColor::from_rgba(0.2, 0.4, 0.6, 0.5).to_string(),
+            "#33669980"
+        );
+    }
+
+    #[test]
+    fn packing() {
+        let color = ThemeColor::from_rgba_u32(0x11223344);
+        assert_eq!(color.to_rgba8(), [0x11, 0x22, 0x33, 0x44]);
+        assert_eq!(color.to_rgba_u32(), 0x11223344);
+        assert_eq!(color, ThemeColor::from_rgba8(0x11, 0x22, 0x33, 0x44));
+        assert_eq!(ThemeColor::WHITE.to_rgba_u32(), 0xFFFFFFFF);
+        assert_eq!(ThemeColor::TRANSPARENT.to_rgba_u32(), 0);
+    }
+}
@@ -0,0 +1,296 @@
+//! Contrast computations (WCAG 2.x and APCA)
+use crate::ThemeColor;
+
+/// Iterations used to find the smallest lightness change that meets a contrast target
+const CONTRAST_SEARCH_STEPS: usize = 16;
+
+// APCA constants (APCA-W3 0.0.98G-4g)
+// Source: https://github.com/Myndex/apca-w3
+/// Exponent of the simplified sRGB transfer curve
+const APCA_TRC: f32 = 2.4;
+/// Luminance coefficients of the red, green and blue components
+const APCA_COEFFICIENTS: [f32; 3] = [0.2126729, 0.7151522, 0.0721750];
+/// Exponents for dark text on a light background (background, text)
+const APCA_NORMAL_EXPONENTS: (f32, f32) = (0.56, 0.57);
+/// Exponents for light text on a dark background (background, text)
+const APCA_REVERSE_EXPONENTS: (f32, f32) = (0.65, 0.62);
+/// Luminance below which colors are soft clamped (and the clamp exponent)
+const APCA_BLACK_THRESHOLD: f32 = 0.022;
+const APCA_BLACK_CLAMP: f32 = 1.414;
+/// Output scale, and offset removed from results above the low clip
+const APCA_SCALE: f32 = 1.14;
+const APCA_LOW_OFFSET: f32 = 0.027;
+const APCA_LOW_CLIP: f32 = 0.1;
+/// Minimum luminance difference to have any contrast
+const APCA_DELTA_MIN: f32 = 0.0005;
+
+/// Contrast target between a text color and its background
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ContrastTarget {
+    /// Minimum WCAG 2.x contrast ratio (1.0 - 21.0)
+    Wcag(f32),
+    /// Minimum APCA lightness contrast (absolute Lc value, 0.0 - 108.0, 60.0 for body text)
+    Apca(f32),
+}
+
+impl ContrastTarget {
+    /// WCAG AA level for body text (4.5:1)
+    pub const WCAG_AA: Self = Self::Wcag(4.5);
+    /// WCAG AAA level for body text (7:1)
+    pub const WCAG_AAA: Self = Self::Wcag(7.0);
+
+    /// Get the contrast of a text color on a background, in the target's scale.
+    fn contrast(self, text: ThemeColor, background: ThemeColor) -> f32 {
+        match self {
+            Self::Wcag(_) => text.contrast_ratio(background),
+            Self::Apca(_) => text.apca_contrast(background).abs(),
+        }
+    }
+
+    /// Check if a text color meets the target on a background.
+    pub fn is_met(self, text: ThemeColor, background: ThemeColor) -> bool {
+        let minimum = match self {
+            Self::Wcag(ratio) => ratio,
+            Self::Apca(lc) => lc.abs(),
+        };
+
+        self.contrast(text, background) >= minimum
+    }
+}
+
+/// Get the APCA screen luminance of a color (soft clamped near black)
+fn apca_luminance(color: ThemeColor) -> f32 {
+    let [red, green, blue] = APCA_COEFFICIENTS;
+    let component = |component: f32| component.max(0.0).powf(APCA_TRC);
+    let luminance =
+        red * component(color.red) + green * component(color.green) + blue * component(color.blue);
+
+    if luminance > APCA_BLACK_THRESHOLD {
+        luminance
+    } else {
+        luminance + (APCA_BLACK_THRESHOLD - luminance).powf(APCA_BLACK_CLAMP)
+    }
+}
+
+impl ThemeColor {
+    /// Get the relative luminance (0.0 - 1.0, as defined by WCAG 2.x).
+    ///
+    /// Alpha is ignored, composite translucent colors over their background first.
+    pub fn relative_luminance(self) -> f32 {
+        let color = self.to_linear_rgb();
+        0.2126 * color.red + 0.7152 * color.green + 0.0722 * color.blue
+    }
+
+    /// Get the contrast ratio with another color (1.0 - 21.0, as defined by WCAG 2.x).
+    pub fn contrast_ratio(self, other: ThemeColor) -> f32 {
+        let first = self.relative_luminance();
+        let second = other.relative_luminance();
+
+        (first.max(second) + 0.05) / (first.min(second) + 0.05)
+    }
+
+    /// Get the APCA lightness contrast (Lc) of this text color on a background.
+    ///
+    /// Positive values are dark text on a light background, negative ones light text on a dark
+    /// background. Absolute values go up to about 108, 60 is the minimum for body text.
+    pub fn apca_contrast(self, background: ThemeColor) -> f32 {
+        let text = apca_luminance(self);
+        let background = apca_luminance(background);
+
+        if (background - text).abs() < APCA_DELTA_MIN {
+            return 0.0;
+        }
+
+        let contrast = if background > text {
+            let (background_exponent, text_exponent) = APCA_NORMAL_EXPONENTS;
+            let contrast =
+                (background.powf(background_exponent) - text.powf(text_exponent)) * APCA_SCALE;
+            if contrast < APCA_LOW_CLIP {
+                0.0
+            } else {
+                contrast - APCA_LOW_OFFSET
+            }
+        } else {
+            let (background_exponent, text_exponent) = APCA_REVERSE_EXPONENTS;
+            let contrast =
+                (background.powf(background_exponent) - text.powf(text_exponent)) * APCA_SCALE;
+            if contrast > -APCA_LOW_CLIP {
+                0.0
+            } else {
+                contrast + APCA_LOW_OFFSET
+            }
+        };
+
+        contrast * 100.0
+    }
+
+    /// Get the readable text color (black or white) with the most contrast on this color.
+    ///
+    /// APCA is used, which matches the white text most desktops use on medium accent colors.
+    pub fn text_color(self) -> ThemeColor {
+        let black = ThemeColor::BLACK.apca_contrast(self).abs();
+        let white = ThemeColor::WHITE.apca_contrast(self).abs();
+
+        if black > white {
+            ThemeColor::BLACK
+        } else {
+            ThemeColor::WHITE
+        }
+    }
+
+    /// Adjust the perceptual lightness (OKLCH) until the color meets a contrast target against a
+    /// background.
+    ///
+    /// The color is moved away from the background as little as possible, keeping its hue. The
+    /// closest color is returned if the target can't be met.
+    pub fn with_contrast(self, background: ThemeColor, target: ContrastTarget) -> Self {
+        if target.is_met(self, background) {
+            return self;
+        }
+
+        // Move toward the extreme with the most contrast against the background
+        let lighter = target.contrast(ThemeColor::WHITE, background)
+            >= target.contrast(ThemeColor::BLACK, background);
+        let lightness = self.to_oklch().lightness;
+        let (direction, range) = if lighter {
+            (1.0, 1.0 - lightness)
+        } else {
+            (-1.0, lightness)
+        };
+
+        let adjust = |amount: f32| self.lighten(direction * amount);
+        if !target.is_met(adjust(range), background) {
+            return adjust(range);
+        }
+
+        // Binary search of the smallest change that meets the target
+        let (mut low, mut high) = (0.0, range);
+        for _ in 0..CONTRAST_SEARCH_STEPS {
+            let amount = (low + high) / 2.0;
+            if target.is_met(adjust(amount), background) {
+                high = amount;
+            } else {
+                low = amount;
+            }
+        }
+
+        adjust(high)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Theme, ThemeContrast, ThemeKind, ThemeScheme};
+
+    fn assert_close(actual: f32, expected: f32, tolerance: f32) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{} is not {} (+/- {})",
+            actual,
+            expected,
+            tolerance
+        );
+    }
+
+    fn gray(value: u8) -> ThemeColor {
+        ThemeColor::from_rgb8(value, value, value)
+    }
+
+    #[test]
+    fn wcag_contrast_ratio() {
+        assert_close(
+            ThemeColor::BLACK.contrast_ratio(ThemeColor::WHITE),
+            21.0,
+            1e-4,
+        );
+        assert_close(
+            ThemeColor::WHITE.contrast_ratio(ThemeColor::BLACK),
+            21.0,
+            1e-4,
+        );
+        assert_close(
+            ThemeColor::WHITE.contrast_ratio(ThemeColor::WHITE),
+            1.0,
+            1e-6,
+        );
+
+        // Commonly cited as just failing AA
+        let ratio = gray(0x77).contrast_ratio(ThemeColor::WHITE);
+        assert_close(ratio, 4.48, 0.005);
+        assert!(!ContrastTarget::WCAG_AA.is_met(gray(0x77), ThemeColor::WHITE));
+    }
+
+    #[test]
+    fn apca_reference_values() {
+        // Text, background and Lc, from the apca-w3 test values
+        let pairs = [
+            (gray(0x88), ThemeColor::WHITE, 63.056469930209424),
+            (ThemeColor::WHITE, gray(0x88), -68.54146436644962),
+            (ThemeColor::BLACK, gray(0xaa), 58.146262578561334),
+            (gray(0xaa), ThemeColor::BLACK, -56.24113336839742),
+            (
+                ThemeColor::from_rgb8(0x11, 0x22, 0x33),
+                ThemeColor::from_rgb8(0xdd, 0xee, 0xff),
+                91.66830811481631,
+            ),
+            (
+                ThemeColor::from_rgb8(0xdd, 0xee, 0xff),
+                ThemeColor::from_rgb8(0x11, 0x22, 0x33),
+                -93.06770049484275,
+            ),
+            (
+                ThemeColor::from_rgb8(0x11, 0x22, 0x33),
+                gray(0x44),
+                8.32326136957393,
+            ),
+            (
+                gray(0x44),
+                ThemeColor::from_rgb8(0x11, 0x22, 0x33),
+                -7.526878460278154,
+            ),
+        ];
+
+        for (text, background, lc) in pairs {
+            assert_close(text.apca_contrast(background), lc as f32, 0.01);
+        }
+
+        assert_eq!(gray(0x88).apca_contrast(gray(0x88)), 0.0);
+    }
+
+    #[test]
+    fn accent_contrast_target() {
+        let targets = [
+            ContrastTarget::WCAG_AA,
+            ContrastTarget::WCAG_AAA,
+            ContrastTarget::Apca(60.0),
+        ];
+
+        for scheme in [ThemeScheme::Light, ThemeScheme::Dark] {
+            for target in targets {
+                let mut theme = Theme::new(ThemeKind::Gtk, scheme, ThemeContrast::Normal, None);
+                // Yellow is hard to read on light backgrounds, and blue on dark ones
+                let accent = match scheme {
+                    ThemeScheme::Light => ThemeColor::from_rgb8(0xf6, 0xd3, 0x2d),
+                    ThemeScheme::Dark => ThemeColor::from_rgb8(0x1c, 0x3d, 0x8c),
+                };
+                theme.palette.set_accent(accent);
+
+                let palette = theme.with_accent_contrast(target).palette;
+                assert!(
+                    target.is_met(palette.accent, palette.background),
+                    "{:?} accent doesn't meet {:?}",
+                    scheme,
+                    target
+                );
+                assert!(
+                    target.is_met(palette.accent_foreground, palette.accent),
+                    "{:?} accent foreground doesn't meet {:?}",
+                    scheme,
+                    target
+                );
+            }
+        }
+    }
+}
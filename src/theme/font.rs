@@ -0,0 +1,59 @@
+//! Theme fonts
+
+/// Regular font weight (CSS scale)
+const REGULAR_WEIGHT: u16 = 400;
+
+/// Theme font style
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThemeFontStyle {
+    /// Upright
+    #[default]
+    Normal,
+    /// Italic
+    Italic,
+    /// Oblique (slanted upright)
+    Oblique,
+}
+
+/// Theme font
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThemeFont {
+    /// Font family name
+    pub family: String,
+    /// Font size (in points)
+    pub size: f32,
+    /// Font weight (100 - 1000, using the CSS scale where 400 is regular and 700 is bold)
+    pub weight: u16,
+    /// Font style
+    pub style: ThemeFontStyle,
+}
+
+impl ThemeFont {
+    /// Create a regular font with the given family and size (in points).
+    pub fn new(family: impl Into<String>, size: f32) -> Self {
+        Self {
+            family: family.into(),
+            size,
+            weight: REGULAR_WEIGHT,
+            style: ThemeFontStyle::Normal,
+        }
+    }
+}
+
+/// Theme fonts
+///
+/// Fonts not configured by the system are `None`.
+#[derive(Debug, Default, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThemeFonts {
+    /// Interface font (used by most widgets)
+    pub interface: Option<ThemeFont>,
+    /// Document font (used for reading long texts)
+    pub document: Option<ThemeFont>,
+    /// Monospace font (used for code and terminals)
+    pub monospace: Option<ThemeFont>,
+    /// Window title bar font
+    pub titlebar: Option<ThemeFont>,
+}
@@ -1,13 +1,28 @@
 //! Theme definitions
+mod color;
+mod contrast;
+mod font;
 mod palette;
+mod space;
+mod tonal;
 
-use std::fmt::Debug;
-
 #[doc(inline)]
-pub use palette::ThemePalette;
+pub use color::ThemeColor;
+#[doc(inline)]
+pub use contrast::ContrastTarget;
+#[doc(inline)]
+pub use font::{ThemeFont, ThemeFontStyle, ThemeFonts};
+#[doc(inline)]
+pub use palette::{ThemeAccentShades, ThemePalette};
+pub(crate) use palette::{ROLES, SHADES};
+#[doc(inline)]
+pub use space::{Hsl, Hsv, LinearRgb, Oklab, Oklch};
+#[doc(inline)]
+pub use tonal::{TonalPalette, TonalRoles, TonalScheme};
 
 /// Theme scheme
 #[derive(Debug, Default, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ThemeScheme {
     /// Light mode
     #[default]
@@ -18,64 +33,73 @@ pub enum ThemeScheme {
 
 /// Theme contrast
 #[derive(Debug, Default, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum ThemeContrast {
     /// Normal contrast
     #[default]
     Normal,
     /// High contrast
     High,
+    /// Reduced contrast (softer colors)
+    Reduced,
+    /// Forced colors (e.g. Windows contrast themes), the system colors should be used as is
+    Forced,
 }
 
-/// Theme color
-#[derive(PartialEq, Clone, Copy)]
-pub struct ThemeColor {
-    /// Red component (0.0 - 1.0)
-    pub red: f32,
-    /// Green component (0.0 - 1.0)
-    pub green: f32,
-    /// Blue component (0.0 - 1.0)
-    pub blue: f32,
+/// Theme motion preference
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThemeMotion {
+    /// Normal animations
+    #[default]
+    Normal,
+    /// Reduced animations (accessibility setting)
+    Reduced,
 }
 
-impl Debug for ThemeColor {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "#{:02X}{:02X}{:02X}",
-            (self.red * 255.0) as u8,
-            (self.green * 255.0) as u8,
-            (self.blue * 255.0) as u8
-        )
-    }
+/// Theme scaling preferences
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThemeScaling {
+    /// Text scale factor (1.0 is the default size)
+    pub text: f32,
+    /// Cursor size (in logical pixels), `None` if not configured
+    pub cursor_size: Option<u32>,
 }
 
-impl ThemeColor {
-    /// White color (#FFFFFF)
-    pub const WHITE: Self = Self {
-        red: 1.0,
-        green: 1.0,
-        blue: 1.0,
-    };
-
-    /// Black color (#000000)
-    pub const BLACK: Self = Self {
-        red: 0.0,
-        green: 0.0,
-        blue: 0.0,
-    };
-
-    /// Create a color from RGB 8-bit values.
-    pub const fn from_rgb8(red: u8, green: u8, blue: u8) -> Self {
+impl Default for ThemeScaling {
+    fn default() -> Self {
         Self {
-            red: red as f32 / 255.0,
-            green: green as f32 / 255.0,
-            blue: blue as f32 / 255.0,
+            text: 1.0,
+            cursor_size: None,
         }
     }
 }
 
+/// Theme change event
+///
+/// Carries the new value of the setting that changed.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum ThemeChange {
+    /// Theme scheme preference changed (`None` if the user no longer has a preference)
+    Scheme(Option<ThemeScheme>),
+    /// Theme contrast level changed
+    Contrast(ThemeContrast),
+    /// Theme accent color changed (`None` if no longer configured)
+    Accent(Option<ThemeColor>),
+    /// Theme motion preference changed
+    Motion(ThemeMotion),
+    /// Theme scaling preferences changed
+    Scaling(ThemeScaling),
+}
+
 /// Theme kind
 #[derive(Debug, Default, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum ThemeKind {
     /// Microsoft Windows
     #[cfg_attr(target_os = "windows", default)]
@@ -88,10 +112,19 @@ pub enum ThemeKind {
     Gtk,
     /// Qt (KDE)
     Qt,
+    /// COSMIC
+    Cosmic,
+    /// Pantheon (elementary OS)
+    Pantheon,
+    /// Greybird (Xfce)
+    Xfce,
+    /// Yaru (Ubuntu)
+    Yaru,
 }
 
 /// Theme
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Theme {
     /// Theme name
     pub name: String,
@@ -118,7 +151,7 @@ impl Theme {
         contrast: ThemeContrast,
         accent: Option<ThemeColor>,
     ) -> Self {
-        let (mut name, mut palette) = match kind {
+        let (name, palette) = match kind {
             ThemeKind::Windows => match scheme {
                 ThemeScheme::Light => ("FluentLight".to_string(), palette::FLUENT_LIGHT),
                 ThemeScheme::Dark => ("FluentDark".to_string(), palette::FLUENT_DARK),
@@ -135,30 +168,95 @@ impl Theme {
                 ThemeScheme::Light => ("BreezeLight".to_string(), palette::BREEZE_LIGHT),
                 ThemeScheme::Dark => ("BreezeDark".to_string(), palette::BREEZE_DARK),
             },
+            ThemeKind::Cosmic => match scheme {
+                ThemeScheme::Light => ("CosmicLight".to_string(), palette::COSMIC_LIGHT),
+                ThemeScheme::Dark => ("CosmicDark".to_string(), palette::COSMIC_DARK),
+            },
+            ThemeKind::Pantheon => match scheme {
+                ThemeScheme::Light => ("PantheonLight".to_string(), palette::PANTHEON_LIGHT),
+                ThemeScheme::Dark => ("PantheonDark".to_string(), palette::PANTHEON_DARK),
+            },
+            ThemeKind::Xfce => match scheme {
+                ThemeScheme::Light => ("GreybirdLight".to_string(), palette::GREYBIRD_LIGHT),
+                ThemeScheme::Dark => ("GreybirdDark".to_string(), palette::GREYBIRD_DARK),
+            },
+            ThemeKind::Yaru => match scheme {
+                ThemeScheme::Light => ("YaruLight".to_string(), palette::YARU_LIGHT),
+                ThemeScheme::Dark => ("YaruDark".to_string(), palette::YARU_DARK),
+            },
         };
 
-        // Change background/foreground colors if high contrast
-        if contrast == ThemeContrast::High {
-            // Append to name the variant
-            name.push_str("HC");
-
-            match scheme {
-                ThemeScheme::Light => {
-                    palette.background = ThemeColor::WHITE;
-                    palette.foreground = ThemeColor::BLACK;
-                }
-                ThemeScheme::Dark => {
-                    palette.background = ThemeColor::BLACK;
-                    palette.foreground = ThemeColor::WHITE;
-                }
-            }
-        }
+        let high_contrast = palette::high_contrast_palette(kind, scheme);
+        let mut theme =
+            Theme { name, palette }.with_contrast_level(contrast, scheme, high_contrast);
 
         // Set accent color if provided
         if let Some(accent) = accent {
-            palette.accent = accent;
+            let palette = &mut theme.palette;
+            palette.set_accent(palette.adjust_accent(accent, contrast));
+        }
+
+        theme
+    }
+
+    /// Get the tonal theme generated from a seed color, in the style of Material 3 (see
+    /// [`ThemePalette::from_seed`]).
+    ///
+    /// Pass `SystemTheme::get_accent()` as the seed to harmonize the whole palette with it.
+    pub fn tonal(seed: ThemeColor, scheme: ThemeScheme, contrast: ThemeContrast) -> Self {
+        let name = match scheme {
+            ThemeScheme::Light => "TonalLight",
+            ThemeScheme::Dark => "TonalDark",
+        };
+        let palette = ThemePalette::from_seed(seed, scheme);
+
+        Theme {
+            name: name.to_string(),
+            palette,
+        }
+        .with_contrast_level(contrast, scheme, None)
+    }
+
+    /// Tune the palette for a contrast level, appending the variant to the name.
+    ///
+    /// The dedicated high contrast palette of the theme is used if it has one.
+    fn with_contrast_level(
+        mut self,
+        contrast: ThemeContrast,
+        scheme: ThemeScheme,
+        high_contrast: Option<ThemePalette>,
+    ) -> Self {
+        match contrast {
+            ThemeContrast::Normal => {}
+            // Forced colors are read from the system if possible, this is the closest fallback
+            ThemeContrast::High | ThemeContrast::Forced => {
+                self.name.push_str("HC");
+                self.palette =
+                    high_contrast.unwrap_or_else(|| self.palette.with_high_contrast(scheme));
+            }
+            ThemeContrast::Reduced => {
+                self.name.push_str("LC");
+                self.palette = self.palette.with_reduced_contrast();
+            }
+        }
+
+        self
+    }
+
+    /// Adjust the accent color (and the text color on it) until they meet a contrast target.
+    ///
+    /// The accent is checked against the background, then the accent foreground against the
+    /// accent. Only the lightness of the colors is changed (in OKLCH).
+    pub fn with_accent_contrast(mut self, target: ContrastTarget) -> Self {
+        let palette = &mut self.palette;
+
+        // Keep the system shades if the accent is already legible
+        let accent = palette.accent.with_contrast(palette.background, target);
+        if accent != palette.accent {
+            palette.set_accent(accent);
         }
 
-        Theme { name, palette }
+        palette.accent_foreground = palette.accent_foreground.with_contrast(accent, target);
+        self
     }
 }
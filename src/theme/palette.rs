@@ -1,6 +1,98 @@
 //! Theme palettes
 
-use crate::ThemeColor;
+use crate::{ThemeColor, ThemeContrast, ThemeKind, ThemeScheme};
+
+/// Amounts of white (lighter shades) or black (darker shades) mixed into the accent color
+const ACCENT_SHADE_STEPS: [f32; 3] = [0.25, 0.45, 0.7];
+
+/// Minimum contrast ratio of the high contrast palettes (WCAG AAA for text)
+const HIGH_CONTRAST_RATIO: f32 = 7.0;
+/// Amount of foreground mixed at each step when increasing the contrast of a color
+const HIGH_CONTRAST_STEP: f32 = 0.05;
+/// Amount of background mixed into the other colors for reduced contrast
+const REDUCED_CONTRAST_MIX: f32 = 0.2;
+/// Amount of view background mixed into the accent color for tinted selections
+const SELECTION_TINT: f32 = 0.7;
+
+/// Get the dedicated high contrast palette of a theme kind, if it has one.
+///
+/// Every color of these palettes has at least AAA contrast against the background, except the
+/// disabled foreground: it keeps the system value, WCAG has no requirement for disabled controls.
+pub(crate) fn high_contrast_palette(kind: ThemeKind, scheme: ThemeScheme) -> Option<ThemePalette> {
+    match (kind, scheme) {
+        (ThemeKind::Windows, ThemeScheme::Light) => Some(FLUENT_HC_LIGHT),
+        (ThemeKind::Windows, ThemeScheme::Dark) => Some(FLUENT_HC_DARK),
+        (ThemeKind::MacOS, ThemeScheme::Light) => Some(AQUA_HC_LIGHT),
+        (ThemeKind::MacOS, ThemeScheme::Dark) => Some(AQUA_HC_DARK),
+        (ThemeKind::Gtk, ThemeScheme::Light) => Some(ADWAITA_HC_LIGHT),
+        (ThemeKind::Gtk, ThemeScheme::Dark) => Some(ADWAITA_HC_DARK),
+        (ThemeKind::Qt, ThemeScheme::Light) => Some(BREEZE_HC_LIGHT),
+        (ThemeKind::Qt, ThemeScheme::Dark) => Some(BREEZE_HC_DARK),
+        _ => None,
+    }
+}
+
+/// Names of the palette color roles (as serialized), accent shades excluded
+pub(crate) const ROLES: [&str; 21] = [
+    "background",
+    "foreground",
+    "view",
+    "sidebar",
+    "headerbar",
+    "card",
+    "border",
+    "muted_foreground",
+    "disabled_foreground",
+    "accent",
+    "accent_foreground",
+    "selection",
+    "selection_foreground",
+    "focus_ring",
+    "link",
+    "visited_link",
+    "tooltip",
+    "tooltip_foreground",
+    "success",
+    "warning",
+    "danger",
+];
+
+/// Names of the accent shades (as serialized)
+pub(crate) const SHADES: [&str; 6] = ["light1", "light2", "light3", "dark1", "dark2", "dark3"];
+
+/// Get the scheme of a background color.
+///
+/// Backgrounds with more contrast against white than black are considered dark.
+fn background_scheme(background: ThemeColor) -> ThemeScheme {
+    if background.contrast_ratio(ThemeColor::WHITE) > background.contrast_ratio(ThemeColor::BLACK) {
+        ThemeScheme::Dark
+    } else {
+        ThemeScheme::Light
+    }
+}
+
+/// Get a selection color tinted with the accent color (as on macOS, GNOME and COSMIC)
+const fn tinted_selection(accent: ThemeColor, view: ThemeColor) -> ThemeColor {
+    accent.mix(view, SELECTION_TINT)
+}
+
+/// Move a color toward the foreground until it has high contrast against the background
+fn with_high_contrast(
+    color: ThemeColor,
+    background: ThemeColor,
+    foreground: ThemeColor,
+) -> ThemeColor {
+    let mut amount = 0.0;
+
+    loop {
+        let tuned = color.mix(foreground, amount);
+        if amount >= 1.0 || tuned.contrast_ratio(background) >= HIGH_CONTRAST_RATIO {
+            return tuned;
+        }
+
+        amount += HIGH_CONTRAST_STEP;
+    }
+}
 
 /// Windows Fluent light theme palette
 ///
@@ -8,7 +100,23 @@ use crate::ThemeColor;
 pub const FLUENT_LIGHT: ThemePalette = ThemePalette {
     background: ThemeColor::from_rgb8(250, 250, 250),
     foreground: ThemeColor::from_rgb8(36, 36, 36),
+    view: ThemeColor::WHITE,
+    sidebar: ThemeColor::from_rgb8(245, 245, 245),
+    headerbar: ThemeColor::from_rgb8(240, 240, 240),
+    card: ThemeColor::WHITE,
+    border: ThemeColor::from_rgb8(209, 209, 209),
+    muted_foreground: ThemeColor::from_rgb8(97, 97, 97),
+    disabled_foreground: ThemeColor::from_rgb8(189, 189, 189),
     accent: ThemeColor::from_rgb8(15, 108, 189),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(15, 108, 189)),
+    accent_foreground: ThemeColor::WHITE,
+    selection: ThemeColor::from_rgb8(15, 108, 189),
+    selection_foreground: ThemeColor::WHITE,
+    focus_ring: ThemeColor::BLACK,
+    link: ThemeColor::from_rgb8(17, 94, 163),
+    visited_link: ThemeColor::from_rgb8(17, 94, 163),
+    tooltip: ThemeColor::WHITE,
+    tooltip_foreground: ThemeColor::from_rgb8(36, 36, 36),
     success: ThemeColor::from_rgb8(14, 112, 14),
     warning: ThemeColor::from_rgb8(188, 75, 9),
     danger: ThemeColor::from_rgb8(177, 14, 28),
@@ -20,19 +128,107 @@ pub const FLUENT_LIGHT: ThemePalette = ThemePalette {
 pub const FLUENT_DARK: ThemePalette = ThemePalette {
     background: ThemeColor::from_rgb8(31, 31, 31),
     foreground: ThemeColor::from_rgb8(255, 255, 255),
+    view: ThemeColor::from_rgb8(41, 41, 41),
+    sidebar: ThemeColor::from_rgb8(20, 20, 20),
+    headerbar: ThemeColor::from_rgb8(10, 10, 10),
+    card: ThemeColor::from_rgb8(51, 51, 51),
+    border: ThemeColor::from_rgb8(102, 102, 102),
+    muted_foreground: ThemeColor::from_rgb8(173, 173, 173),
+    disabled_foreground: ThemeColor::from_rgb8(92, 92, 92),
     accent: ThemeColor::from_rgb8(71, 158, 245),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(71, 158, 245)),
+    accent_foreground: ThemeColor::BLACK,
+    selection: ThemeColor::from_rgb8(71, 158, 245),
+    selection_foreground: ThemeColor::BLACK,
+    focus_ring: ThemeColor::WHITE,
+    link: ThemeColor::from_rgb8(71, 158, 245),
+    visited_link: ThemeColor::from_rgb8(71, 158, 245),
+    tooltip: ThemeColor::from_rgb8(41, 41, 41),
+    tooltip_foreground: ThemeColor::WHITE,
     success: ThemeColor::from_rgb8(84, 176, 84),
     warning: ThemeColor::from_rgb8(250, 160, 107),
     danger: ThemeColor::from_rgb8(220, 98, 109),
 };
 
+/// Windows Fluent high contrast light theme palette (Desert contrast theme)
+///
+/// Source: https://learn.microsoft.com/en-us/windows/apps/design/accessibility/high-contrast-themes
+pub const FLUENT_HC_LIGHT: ThemePalette = ThemePalette {
+    background: ThemeColor::from_rgb8(255, 250, 239),
+    foreground: ThemeColor::from_rgb8(61, 61, 61),
+    view: ThemeColor::from_rgb8(255, 250, 239),
+    sidebar: ThemeColor::from_rgb8(255, 250, 239),
+    headerbar: ThemeColor::from_rgb8(255, 250, 239),
+    card: ThemeColor::from_rgb8(255, 250, 239),
+    border: ThemeColor::from_rgb8(61, 61, 61),
+    muted_foreground: ThemeColor::from_rgb8(61, 61, 61),
+    disabled_foreground: ThemeColor::from_rgb8(103, 103, 103),
+    accent: ThemeColor::from_rgb8(30, 92, 114),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(30, 92, 114)),
+    accent_foreground: ThemeColor::WHITE,
+    selection: ThemeColor::from_rgb8(144, 57, 9),
+    selection_foreground: ThemeColor::from_rgb8(255, 245, 227),
+    focus_ring: ThemeColor::from_rgb8(61, 61, 61),
+    link: ThemeColor::from_rgb8(30, 92, 114),
+    visited_link: ThemeColor::from_rgb8(30, 92, 114),
+    tooltip: ThemeColor::from_rgb8(255, 250, 239),
+    tooltip_foreground: ThemeColor::from_rgb8(61, 61, 61),
+    success: ThemeColor::from_rgb8(26, 99, 26),
+    warning: ThemeColor::from_rgb8(131, 69, 32),
+    danger: ThemeColor::from_rgb8(171, 16, 30),
+};
+
+/// Windows Fluent high contrast dark theme palette (Aquatic contrast theme)
+///
+/// Source: https://learn.microsoft.com/en-us/windows/apps/design/accessibility/high-contrast-themes
+pub const FLUENT_HC_DARK: ThemePalette = ThemePalette {
+    background: ThemeColor::from_rgb8(32, 32, 32),
+    foreground: ThemeColor::from_rgb8(255, 255, 255),
+    view: ThemeColor::from_rgb8(32, 32, 32),
+    sidebar: ThemeColor::from_rgb8(32, 32, 32),
+    headerbar: ThemeColor::from_rgb8(32, 32, 32),
+    card: ThemeColor::from_rgb8(32, 32, 32),
+    border: ThemeColor::from_rgb8(255, 255, 255),
+    muted_foreground: ThemeColor::from_rgb8(255, 255, 255),
+    disabled_foreground: ThemeColor::from_rgb8(166, 166, 166),
+    accent: ThemeColor::from_rgb8(117, 233, 252),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(117, 233, 252)),
+    accent_foreground: ThemeColor::BLACK,
+    selection: ThemeColor::from_rgb8(142, 227, 240),
+    selection_foreground: ThemeColor::from_rgb8(38, 59, 80),
+    focus_ring: ThemeColor::from_rgb8(255, 255, 255),
+    link: ThemeColor::from_rgb8(117, 233, 252),
+    visited_link: ThemeColor::from_rgb8(117, 233, 252),
+    tooltip: ThemeColor::from_rgb8(32, 32, 32),
+    tooltip_foreground: ThemeColor::from_rgb8(255, 255, 255),
+    success: ThemeColor::from_rgb8(110, 188, 110),
+    warning: ThemeColor::from_rgb8(250, 160, 107),
+    danger: ThemeColor::from_rgb8(232, 153, 160),
+};
+
 /// Apple Aqua light theme palette
 ///
 /// Source: https://developer.apple.com/design/human-interface-guidelines/color
 pub const AQUA_LIGHT: ThemePalette = ThemePalette {
     background: ThemeColor::from_rgb8(229, 229, 234),
     foreground: ThemeColor::from_rgb8(28, 28, 30),
+    view: ThemeColor::WHITE,
+    sidebar: ThemeColor::from_rgb8(242, 242, 247),
+    headerbar: ThemeColor::from_rgb8(246, 246, 246),
+    card: ThemeColor::WHITE,
+    border: ThemeColor::from_rgb8(198, 198, 200),
+    muted_foreground: ThemeColor::from_rgb8(138, 138, 142),
+    disabled_foreground: ThemeColor::from_rgb8(196, 196, 198),
     accent: ThemeColor::from_rgb8(0, 136, 255),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(0, 136, 255)),
+    accent_foreground: ThemeColor::WHITE,
+    selection: tinted_selection(ThemeColor::from_rgb8(0, 136, 255), ThemeColor::WHITE),
+    selection_foreground: ThemeColor::from_rgb8(28, 28, 30),
+    focus_ring: ThemeColor::from_rgb8(0, 136, 255),
+    link: ThemeColor::from_rgb8(0, 104, 218),
+    visited_link: ThemeColor::from_rgb8(175, 82, 222),
+    tooltip: ThemeColor::from_rgb8(239, 239, 239),
+    tooltip_foreground: ThemeColor::from_rgb8(28, 28, 30),
     success: ThemeColor::from_rgb8(52, 199, 89),
     warning: ThemeColor::from_rgb8(255, 141, 40),
     danger: ThemeColor::from_rgb8(255, 56, 60),
@@ -44,19 +240,111 @@ pub const AQUA_LIGHT: ThemePalette = ThemePalette {
 pub const AQUA_DARK: ThemePalette = ThemePalette {
     background: ThemeColor::from_rgb8(44, 44, 46),
     foreground: ThemeColor::from_rgb8(242, 242, 247),
+    view: ThemeColor::from_rgb8(30, 30, 30),
+    sidebar: ThemeColor::from_rgb8(28, 28, 30),
+    headerbar: ThemeColor::from_rgb8(56, 56, 58),
+    card: ThemeColor::from_rgb8(58, 58, 60),
+    border: ThemeColor::from_rgb8(56, 56, 58),
+    muted_foreground: ThemeColor::from_rgb8(152, 152, 159),
+    disabled_foreground: ThemeColor::from_rgb8(92, 92, 96),
     accent: ThemeColor::from_rgb8(0, 145, 255),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(0, 145, 255)),
+    accent_foreground: ThemeColor::WHITE,
+    selection: tinted_selection(
+        ThemeColor::from_rgb8(0, 145, 255),
+        ThemeColor::from_rgb8(30, 30, 30),
+    ),
+    selection_foreground: ThemeColor::from_rgb8(242, 242, 247),
+    focus_ring: ThemeColor::from_rgb8(0, 145, 255),
+    link: ThemeColor::from_rgb8(65, 156, 255),
+    visited_link: ThemeColor::from_rgb8(191, 90, 242),
+    tooltip: ThemeColor::from_rgb8(50, 50, 52),
+    tooltip_foreground: ThemeColor::from_rgb8(242, 242, 247),
     success: ThemeColor::from_rgb8(48, 209, 88),
     warning: ThemeColor::from_rgb8(255, 146, 48),
     danger: ThemeColor::from_rgb8(255, 66, 69),
 };
 
+/// Apple Aqua high contrast light theme palette (increased contrast colors)
+///
+/// Source: https://developer.apple.com/design/human-interface-guidelines/color#Specifications
+pub const AQUA_HC_LIGHT: ThemePalette = ThemePalette {
+    background: ThemeColor::from_rgb8(255, 255, 255),
+    foreground: ThemeColor::from_rgb8(0, 0, 0),
+    view: ThemeColor::from_rgb8(255, 255, 255),
+    sidebar: ThemeColor::from_rgb8(255, 255, 255),
+    headerbar: ThemeColor::from_rgb8(255, 255, 255),
+    card: ThemeColor::from_rgb8(255, 255, 255),
+    border: ThemeColor::from_rgb8(0, 0, 0),
+    muted_foreground: ThemeColor::from_rgb8(60, 60, 67),
+    disabled_foreground: ThemeColor::from_rgb8(84, 84, 88),
+    accent: ThemeColor::from_rgb8(0, 64, 221),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(0, 64, 221)),
+    accent_foreground: ThemeColor::WHITE,
+    selection: ThemeColor::from_rgb8(0, 64, 221),
+    selection_foreground: ThemeColor::WHITE,
+    focus_ring: ThemeColor::from_rgb8(0, 64, 221),
+    link: ThemeColor::from_rgb8(0, 64, 221),
+    visited_link: ThemeColor::from_rgb8(110, 50, 140),
+    tooltip: ThemeColor::from_rgb8(255, 255, 255),
+    tooltip_foreground: ThemeColor::from_rgb8(0, 0, 0),
+    success: ThemeColor::from_rgb8(25, 97, 43),
+    warning: ThemeColor::from_rgb8(161, 42, 0),
+    danger: ThemeColor::from_rgb8(172, 0, 17),
+};
+
+/// Apple Aqua high contrast dark theme palette (increased contrast colors)
+///
+/// Source: https://developer.apple.com/design/human-interface-guidelines/color#Specifications
+pub const AQUA_HC_DARK: ThemePalette = ThemePalette {
+    background: ThemeColor::from_rgb8(0, 0, 0),
+    foreground: ThemeColor::from_rgb8(255, 255, 255),
+    view: ThemeColor::from_rgb8(0, 0, 0),
+    sidebar: ThemeColor::from_rgb8(0, 0, 0),
+    headerbar: ThemeColor::from_rgb8(0, 0, 0),
+    card: ThemeColor::from_rgb8(0, 0, 0),
+    border: ThemeColor::from_rgb8(255, 255, 255),
+    muted_foreground: ThemeColor::from_rgb8(235, 235, 245),
+    disabled_foreground: ThemeColor::from_rgb8(174, 174, 178),
+    accent: ThemeColor::from_rgb8(64, 156, 255),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(64, 156, 255)),
+    accent_foreground: ThemeColor::BLACK,
+    selection: ThemeColor::from_rgb8(64, 156, 255),
+    selection_foreground: ThemeColor::BLACK,
+    focus_ring: ThemeColor::from_rgb8(64, 156, 255),
+    link: ThemeColor::from_rgb8(64, 156, 255),
+    visited_link: ThemeColor::from_rgb8(218, 143, 255),
+    tooltip: ThemeColor::from_rgb8(0, 0, 0),
+    tooltip_foreground: ThemeColor::from_rgb8(255, 255, 255),
+    success: ThemeColor::from_rgb8(48, 219, 91),
+    warning: ThemeColor::from_rgb8(255, 179, 64),
+    danger: ThemeColor::from_rgb8(255, 105, 97),
+};
+
 /// GNOME Adwaita light theme palette
 ///
 /// Source: https://github.com/FedoraQt/QGnomePlatform/blob/master/src/color-schemes/Adwaita.colors
+/// (accent from https://gnome.pages.gitlab.gnome.org/libadwaita/doc/main/css-variables.html)
 pub const ADWAITA_LIGHT: ThemePalette = ThemePalette {
     background: ThemeColor::from_rgb8(246, 245, 244),
     foreground: ThemeColor::from_rgb8(25, 25, 25),
-    accent: ThemeColor::from_rgb8(61, 174, 233),
+    view: ThemeColor::WHITE,
+    sidebar: ThemeColor::from_rgb8(235, 235, 235),
+    headerbar: ThemeColor::from_rgb8(235, 235, 235),
+    card: ThemeColor::WHITE,
+    border: ThemeColor::from_rgb8(205, 199, 194),
+    muted_foreground: ThemeColor::from_rgb8(124, 124, 124),
+    disabled_foreground: ThemeColor::from_rgb8(146, 149, 149),
+    accent: ThemeColor::from_rgb8(53, 132, 228),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(53, 132, 228)),
+    accent_foreground: ThemeColor::WHITE,
+    selection: tinted_selection(ThemeColor::from_rgb8(53, 132, 228), ThemeColor::WHITE),
+    selection_foreground: ThemeColor::from_rgb8(25, 25, 25),
+    focus_ring: ThemeColor::from_rgb8(53, 132, 228),
+    link: ThemeColor::from_rgb8(27, 106, 203),
+    visited_link: ThemeColor::from_rgb8(27, 90, 167),
+    tooltip: ThemeColor::from_rgb8(49, 49, 49),
+    tooltip_foreground: ThemeColor::WHITE,
     success: ThemeColor::from_rgb8(39, 174, 96),
     warning: ThemeColor::from_rgb8(246, 116, 0),
     danger: ThemeColor::from_rgb8(218, 68, 83),
@@ -65,22 +353,114 @@ pub const ADWAITA_LIGHT: ThemePalette = ThemePalette {
 /// GNOME Adwaita dark theme palette
 ///
 /// Source: https://github.com/FedoraQt/QGnomePlatform/blob/master/src/color-schemes/AdwaitaDark.colors
+/// (accent from https://gnome.pages.gitlab.gnome.org/libadwaita/doc/main/css-variables.html)
 pub const ADWAITA_DARK: ThemePalette = ThemePalette {
     background: ThemeColor::from_rgb8(45, 45, 45),
     foreground: ThemeColor::from_rgb8(255, 255, 255),
-    accent: ThemeColor::from_rgb8(61, 174, 233),
+    view: ThemeColor::from_rgb8(30, 30, 30),
+    sidebar: ThemeColor::from_rgb8(48, 48, 48),
+    headerbar: ThemeColor::from_rgb8(48, 48, 48),
+    card: ThemeColor::from_rgb8(56, 56, 56),
+    border: ThemeColor::from_rgb8(27, 27, 27),
+    muted_foreground: ThemeColor::from_rgb8(160, 160, 160),
+    disabled_foreground: ThemeColor::from_rgb8(145, 145, 144),
+    accent: ThemeColor::from_rgb8(53, 132, 228),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(53, 132, 228)),
+    accent_foreground: ThemeColor::WHITE,
+    selection: tinted_selection(
+        ThemeColor::from_rgb8(53, 132, 228),
+        ThemeColor::from_rgb8(30, 30, 30),
+    ),
+    selection_foreground: ThemeColor::from_rgb8(255, 255, 255),
+    focus_ring: ThemeColor::from_rgb8(53, 132, 228),
+    link: ThemeColor::from_rgb8(120, 174, 237),
+    visited_link: ThemeColor::from_rgb8(147, 190, 241),
+    tooltip: ThemeColor::from_rgb8(9, 9, 9),
+    tooltip_foreground: ThemeColor::WHITE,
     success: ThemeColor::from_rgb8(39, 174, 96),
     warning: ThemeColor::from_rgb8(246, 116, 0),
     danger: ThemeColor::from_rgb8(218, 68, 83),
 };
 
+/// GNOME Adwaita high contrast light theme palette (HighContrast)
+///
+/// Source: https://gnome.pages.gitlab.gnome.org/libadwaita/doc/main/css-variables.html
+pub const ADWAITA_HC_LIGHT: ThemePalette = ThemePalette {
+    background: ThemeColor::from_rgb8(255, 255, 255),
+    foreground: ThemeColor::from_rgb8(0, 0, 0),
+    view: ThemeColor::from_rgb8(255, 255, 255),
+    sidebar: ThemeColor::from_rgb8(255, 255, 255),
+    headerbar: ThemeColor::from_rgb8(255, 255, 255),
+    card: ThemeColor::from_rgb8(255, 255, 255),
+    border: ThemeColor::from_rgb8(0, 0, 0),
+    muted_foreground: ThemeColor::from_rgb8(26, 26, 26),
+    disabled_foreground: ThemeColor::from_rgb8(80, 80, 80),
+    accent: ThemeColor::from_rgb8(4, 87, 171),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(4, 87, 171)),
+    accent_foreground: ThemeColor::WHITE,
+    selection: ThemeColor::from_rgb8(4, 87, 171),
+    selection_foreground: ThemeColor::WHITE,
+    focus_ring: ThemeColor::from_rgb8(4, 87, 171),
+    link: ThemeColor::from_rgb8(4, 87, 171),
+    visited_link: ThemeColor::from_rgb8(27, 70, 137),
+    tooltip: ThemeColor::from_rgb8(255, 255, 255),
+    tooltip_foreground: ThemeColor::from_rgb8(0, 0, 0),
+    success: ThemeColor::from_rgb8(20, 100, 62),
+    warning: ThemeColor::from_rgb8(117, 82, 2),
+    danger: ThemeColor::from_rgb8(173, 25, 36),
+};
+
+/// GNOME Adwaita high contrast dark theme palette (HighContrastInverse)
+///
+/// Source: https://gnome.pages.gitlab.gnome.org/libadwaita/doc/main/css-variables.html
+pub const ADWAITA_HC_DARK: ThemePalette = ThemePalette {
+    background: ThemeColor::from_rgb8(0, 0, 0),
+    foreground: ThemeColor::from_rgb8(255, 255, 255),
+    view: ThemeColor::from_rgb8(0, 0, 0),
+    sidebar: ThemeColor::from_rgb8(0, 0, 0),
+    headerbar: ThemeColor::from_rgb8(0, 0, 0),
+    card: ThemeColor::from_rgb8(0, 0, 0),
+    border: ThemeColor::from_rgb8(255, 255, 255),
+    muted_foreground: ThemeColor::from_rgb8(230, 230, 230),
+    disabled_foreground: ThemeColor::from_rgb8(170, 170, 170),
+    accent: ThemeColor::from_rgb8(129, 208, 255),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(129, 208, 255)),
+    accent_foreground: ThemeColor::BLACK,
+    selection: ThemeColor::from_rgb8(129, 208, 255),
+    selection_foreground: ThemeColor::BLACK,
+    focus_ring: ThemeColor::from_rgb8(129, 208, 255),
+    link: ThemeColor::from_rgb8(129, 208, 255),
+    visited_link: ThemeColor::from_rgb8(154, 217, 255),
+    tooltip: ThemeColor::from_rgb8(0, 0, 0),
+    tooltip_foreground: ThemeColor::from_rgb8(255, 255, 255),
+    success: ThemeColor::from_rgb8(143, 240, 164),
+    warning: ThemeColor::from_rgb8(248, 228, 92),
+    danger: ThemeColor::from_rgb8(255, 123, 99),
+};
+
 /// KDE Breeze light theme palette
 ///
 /// Source: https://github.com/KDE/breeze/blob/master/colors/BreezeLight.colors
 pub const BREEZE_LIGHT: ThemePalette = ThemePalette {
-    background: ThemeColor::from_rgb8(255, 255, 255),
+    background: ThemeColor::from_rgb8(239, 240, 241),
     foreground: ThemeColor::from_rgb8(35, 38, 41),
+    view: ThemeColor::WHITE,
+    sidebar: ThemeColor::from_rgb8(239, 240, 241),
+    headerbar: ThemeColor::from_rgb8(222, 224, 226),
+    card: ThemeColor::WHITE,
+    border: ThemeColor::from_rgb8(188, 190, 191),
+    muted_foreground: ThemeColor::from_rgb8(112, 125, 138),
+    disabled_foreground: ThemeColor::from_rgb8(161, 169, 177),
     accent: ThemeColor::from_rgb8(61, 174, 233),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(61, 174, 233)),
+    accent_foreground: ThemeColor::WHITE,
+    selection: ThemeColor::from_rgb8(61, 174, 233),
+    selection_foreground: ThemeColor::WHITE,
+    focus_ring: ThemeColor::from_rgb8(61, 174, 233),
+    link: ThemeColor::from_rgb8(41, 128, 185),
+    visited_link: ThemeColor::from_rgb8(155, 89, 182),
+    tooltip: ThemeColor::from_rgb8(247, 247, 247),
+    tooltip_foreground: ThemeColor::from_rgb8(35, 38, 41),
     success: ThemeColor::from_rgb8(39, 174, 96),
     warning: ThemeColor::from_rgb8(246, 116, 0),
     danger: ThemeColor::from_rgb8(218, 68, 83),
@@ -90,23 +470,437 @@ pub const BREEZE_LIGHT: ThemePalette = ThemePalette {
 ///
 /// Source: https://github.com/KDE/breeze/blob/master/colors/BreezeDark.colors
 pub const BREEZE_DARK: ThemePalette = ThemePalette {
-    background: ThemeColor::from_rgb8(20, 22, 24),
+    background: ThemeColor::from_rgb8(32, 35, 38),
     foreground: ThemeColor::from_rgb8(252, 252, 252),
+    view: ThemeColor::from_rgb8(20, 22, 24),
+    sidebar: ThemeColor::from_rgb8(32, 35, 38),
+    headerbar: ThemeColor::from_rgb8(41, 44, 48),
+    card: ThemeColor::from_rgb8(20, 22, 24),
+    border: ThemeColor::from_rgb8(87, 89, 92),
+    muted_foreground: ThemeColor::from_rgb8(161, 169, 177),
+    disabled_foreground: ThemeColor::from_rgb8(110, 116, 122),
     accent: ThemeColor::from_rgb8(61, 174, 233),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(61, 174, 233)),
+    accent_foreground: ThemeColor::WHITE,
+    selection: ThemeColor::from_rgb8(61, 174, 233),
+    selection_foreground: ThemeColor::from_rgb8(252, 252, 252),
+    focus_ring: ThemeColor::from_rgb8(61, 174, 233),
+    link: ThemeColor::from_rgb8(29, 153, 243),
+    visited_link: ThemeColor::from_rgb8(155, 89, 182),
+    tooltip: ThemeColor::from_rgb8(41, 44, 48),
+    tooltip_foreground: ThemeColor::from_rgb8(252, 252, 252),
     success: ThemeColor::from_rgb8(39, 174, 96),
     warning: ThemeColor::from_rgb8(246, 116, 0),
     danger: ThemeColor::from_rgb8(218, 68, 83),
 };
 
+/// KDE Breeze high contrast light theme palette
+///
+/// Breeze has no high contrast color scheme, this one is derived from Breeze Light: black text on
+/// white, with the accent and status colors darkened to AAA contrast.
+///
+/// Source: https://github.com/KDE/breeze/blob/master/colors/BreezeLight.colors
+pub const BREEZE_HC_LIGHT: ThemePalette = ThemePalette {
+    background: ThemeColor::from_rgb8(255, 255, 255),
+    foreground: ThemeColor::from_rgb8(0, 0, 0),
+    view: ThemeColor::from_rgb8(255, 255, 255),
+    sidebar: ThemeColor::from_rgb8(255, 255, 255),
+    headerbar: ThemeColor::from_rgb8(255, 255, 255),
+    card: ThemeColor::from_rgb8(255, 255, 255),
+    border: ThemeColor::from_rgb8(0, 0, 0),
+    muted_foreground: ThemeColor::from_rgb8(40, 40, 40),
+    disabled_foreground: ThemeColor::from_rgb8(85, 85, 85),
+    accent: ThemeColor::from_rgb8(31, 87, 117),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(31, 87, 117)),
+    accent_foreground: ThemeColor::WHITE,
+    selection: ThemeColor::from_rgb8(31, 87, 117),
+    selection_foreground: ThemeColor::WHITE,
+    focus_ring: ThemeColor::from_rgb8(31, 87, 117),
+    link: ThemeColor::from_rgb8(31, 87, 117),
+    visited_link: ThemeColor::from_rgb8(108, 47, 135),
+    tooltip: ThemeColor::from_rgb8(255, 255, 255),
+    tooltip_foreground: ThemeColor::from_rgb8(0, 0, 0),
+    success: ThemeColor::from_rgb8(21, 96, 53),
+    warning: ThemeColor::from_rgb8(135, 64, 0),
+    danger: ThemeColor::from_rgb8(153, 48, 58),
+};
+
+/// KDE Breeze high contrast dark theme palette
+///
+/// Breeze has no high contrast color scheme, this one is derived from Breeze Dark: white text on
+/// black, with the colors lacking AAA contrast lightened (danger and visited links) or replaced by
+/// the accent (links).
+///
+/// Source: https://github.com/KDE/breeze/blob/master/colors/BreezeDark.colors
+pub const BREEZE_HC_DARK: ThemePalette = ThemePalette {
+    background: ThemeColor::from_rgb8(0, 0, 0),
+    foreground: ThemeColor::from_rgb8(255, 255, 255),
+    view: ThemeColor::from_rgb8(0, 0, 0),
+    sidebar: ThemeColor::from_rgb8(0, 0, 0),
+    headerbar: ThemeColor::from_rgb8(0, 0, 0),
+    card: ThemeColor::from_rgb8(0, 0, 0),
+    border: ThemeColor::from_rgb8(255, 255, 255),
+    muted_foreground: ThemeColor::from_rgb8(200, 200, 200),
+    disabled_foreground: ThemeColor::from_rgb8(160, 160, 160),
+    accent: ThemeColor::from_rgb8(61, 174, 233),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(61, 174, 233)),
+    accent_foreground: ThemeColor::BLACK,
+    selection: ThemeColor::from_rgb8(61, 174, 233),
+    selection_foreground: ThemeColor::BLACK,
+    focus_ring: ThemeColor::from_rgb8(61, 174, 233),
+    link: ThemeColor::from_rgb8(61, 174, 233),
+    visited_link: ThemeColor::from_rgb8(195, 150, 215),
+    tooltip: ThemeColor::from_rgb8(0, 0, 0),
+    tooltip_foreground: ThemeColor::from_rgb8(255, 255, 255),
+    success: ThemeColor::from_rgb8(39, 174, 96),
+    warning: ThemeColor::from_rgb8(246, 116, 0),
+    danger: ThemeColor::from_rgb8(227, 115, 126),
+};
+
+/// COSMIC light theme palette
+///
+/// Source: https://github.com/pop-os/libcosmic/tree/master/cosmic-theme
+pub const COSMIC_LIGHT: ThemePalette = ThemePalette {
+    background: ThemeColor::from_rgb8(242, 242, 242),
+    foreground: ThemeColor::from_rgb8(27, 27, 27),
+    view: ThemeColor::from_rgb8(250, 250, 250),
+    sidebar: ThemeColor::from_rgb8(232, 232, 232),
+    headerbar: ThemeColor::from_rgb8(242, 242, 242),
+    card: ThemeColor::from_rgb8(250, 250, 250),
+    border: ThemeColor::from_rgb8(203, 203, 203),
+    muted_foreground: ThemeColor::from_rgb8(88, 88, 88),
+    disabled_foreground: ThemeColor::from_rgb8(155, 155, 155),
+    accent: ThemeColor::from_rgb8(0, 82, 90),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(0, 82, 90)),
+    accent_foreground: ThemeColor::WHITE,
+    selection: tinted_selection(
+        ThemeColor::from_rgb8(0, 82, 90),
+        ThemeColor::from_rgb8(250, 250, 250),
+    ),
+    selection_foreground: ThemeColor::from_rgb8(27, 27, 27),
+    focus_ring: ThemeColor::from_rgb8(0, 82, 90),
+    link: ThemeColor::from_rgb8(0, 82, 90),
+    visited_link: ThemeColor::from_rgb8(101, 55, 140),
+    tooltip: ThemeColor::from_rgb8(250, 250, 250),
+    tooltip_foreground: ThemeColor::from_rgb8(27, 27, 27),
+    success: ThemeColor::from_rgb8(24, 85, 41),
+    warning: ThemeColor::from_rgb8(83, 72, 0),
+    danger: ThemeColor::from_rgb8(160, 37, 43),
+};
+
+/// COSMIC dark theme palette
+///
+/// Source: https://github.com/pop-os/libcosmic/tree/master/cosmic-theme
+pub const COSMIC_DARK: ThemePalette = ThemePalette {
+    background: ThemeColor::from_rgb8(27, 27, 27),
+    foreground: ThemeColor::from_rgb8(230, 230, 230),
+    view: ThemeColor::from_rgb8(39, 39, 39),
+    sidebar: ThemeColor::from_rgb8(35, 35, 35),
+    headerbar: ThemeColor::from_rgb8(27, 27, 27),
+    card: ThemeColor::from_rgb8(46, 46, 46),
+    border: ThemeColor::from_rgb8(72, 72, 72),
+    muted_foreground: ThemeColor::from_rgb8(161, 161, 161),
+    disabled_foreground: ThemeColor::from_rgb8(100, 100, 100),
+    accent: ThemeColor::from_rgb8(148, 235, 235),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(148, 235, 235)),
+    accent_foreground: ThemeColor::BLACK,
+    selection: tinted_selection(
+        ThemeColor::from_rgb8(148, 235, 235),
+        ThemeColor::from_rgb8(39, 39, 39),
+    ),
+    selection_foreground: ThemeColor::from_rgb8(230, 230, 230),
+    focus_ring: ThemeColor::from_rgb8(148, 235, 235),
+    link: ThemeColor::from_rgb8(148, 235, 235),
+    visited_link: ThemeColor::from_rgb8(216, 183, 255),
+    tooltip: ThemeColor::from_rgb8(46, 46, 46),
+    tooltip_foreground: ThemeColor::from_rgb8(230, 230, 230),
+    success: ThemeColor::from_rgb8(146, 207, 156),
+    warning: ThemeColor::from_rgb8(247, 224, 98),
+    danger: ThemeColor::from_rgb8(255, 181, 181),
+};
+
+/// elementary OS Pantheon light theme palette
+///
+/// Source: https://elementary.io/brand#color
+pub const PANTHEON_LIGHT: ThemePalette = ThemePalette {
+    background: ThemeColor::from_rgb8(250, 250, 250),
+    foreground: ThemeColor::from_rgb8(51, 51, 51),
+    view: ThemeColor::WHITE,
+    sidebar: ThemeColor::from_rgb8(240, 240, 240),
+    headerbar: ThemeColor::from_rgb8(245, 245, 245),
+    card: ThemeColor::WHITE,
+    border: ThemeColor::from_rgb8(213, 213, 213),
+    muted_foreground: ThemeColor::from_rgb8(111, 111, 111),
+    disabled_foreground: ThemeColor::from_rgb8(150, 150, 150),
+    accent: ThemeColor::from_rgb8(54, 137, 230),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(54, 137, 230)),
+    accent_foreground: ThemeColor::WHITE,
+    selection: ThemeColor::from_rgb8(54, 137, 230),
+    selection_foreground: ThemeColor::WHITE,
+    focus_ring: ThemeColor::from_rgb8(54, 137, 230),
+    link: ThemeColor::from_rgb8(13, 82, 191),
+    visited_link: ThemeColor::from_rgb8(114, 57, 179),
+    tooltip: ThemeColor::from_rgb8(50, 50, 50),
+    tooltip_foreground: ThemeColor::WHITE,
+    success: ThemeColor::from_rgb8(104, 183, 35),
+    warning: ThemeColor::from_rgb8(243, 115, 41),
+    danger: ThemeColor::from_rgb8(198, 38, 46),
+};
+
+/// elementary OS Pantheon dark theme palette
+///
+/// Source: https://elementary.io/brand#color
+pub const PANTHEON_DARK: ThemePalette = ThemePalette {
+    background: ThemeColor::from_rgb8(51, 51, 51),
+    foreground: ThemeColor::from_rgb8(250, 250, 250),
+    view: ThemeColor::from_rgb8(41, 41, 41),
+    sidebar: ThemeColor::from_rgb8(45, 45, 45),
+    headerbar: ThemeColor::from_rgb8(61, 61, 61),
+    card: ThemeColor::from_rgb8(61, 61, 61),
+    border: ThemeColor::from_rgb8(26, 26, 26),
+    muted_foreground: ThemeColor::from_rgb8(190, 190, 190),
+    disabled_foreground: ThemeColor::from_rgb8(150, 150, 150),
+    accent: ThemeColor::from_rgb8(100, 186, 255),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(100, 186, 255)),
+    accent_foreground: ThemeColor::BLACK,
+    selection: ThemeColor::from_rgb8(100, 186, 255),
+    selection_foreground: ThemeColor::BLACK,
+    focus_ring: ThemeColor::from_rgb8(100, 186, 255),
+    link: ThemeColor::from_rgb8(100, 186, 255),
+    visited_link: ThemeColor::from_rgb8(205, 158, 247),
+    tooltip: ThemeColor::from_rgb8(10, 10, 10),
+    tooltip_foreground: ThemeColor::WHITE,
+    success: ThemeColor::from_rgb8(155, 219, 77),
+    warning: ThemeColor::from_rgb8(255, 161, 68),
+    danger: ThemeColor::from_rgb8(237, 83, 83),
+};
+
+/// Xfce Greybird light theme palette
+///
+/// Source: https://github.com/shimmerproject/Greybird
+pub const GREYBIRD_LIGHT: ThemePalette = ThemePalette {
+    background: ThemeColor::from_rgb8(252, 252, 252),
+    foreground: ThemeColor::from_rgb8(60, 60, 60),
+    view: ThemeColor::WHITE,
+    sidebar: ThemeColor::from_rgb8(236, 236, 236),
+    headerbar: ThemeColor::from_rgb8(206, 206, 206),
+    card: ThemeColor::WHITE,
+    border: ThemeColor::from_rgb8(166, 166, 166),
+    muted_foreground: ThemeColor::from_rgb8(118, 118, 118),
+    disabled_foreground: ThemeColor::from_rgb8(152, 152, 152),
+    accent: ThemeColor::from_rgb8(57, 142, 231),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(57, 142, 231)),
+    accent_foreground: ThemeColor::WHITE,
+    selection: ThemeColor::from_rgb8(57, 142, 231),
+    selection_foreground: ThemeColor::WHITE,
+    focus_ring: ThemeColor::from_rgb8(57, 142, 231),
+    link: ThemeColor::from_rgb8(42, 118, 198),
+    visited_link: ThemeColor::from_rgb8(132, 90, 178),
+    tooltip: ThemeColor::from_rgb8(51, 51, 51),
+    tooltip_foreground: ThemeColor::WHITE,
+    success: ThemeColor::from_rgb8(78, 154, 6),
+    warning: ThemeColor::from_rgb8(245, 121, 0),
+    danger: ThemeColor::from_rgb8(204, 0, 0),
+};
+
+/// Xfce Greybird dark theme palette
+///
+/// Source: https://github.com/shimmerproject/Greybird
+pub const GREYBIRD_DARK: ThemePalette = ThemePalette {
+    background: ThemeColor::from_rgb8(43, 46, 47),
+    foreground: ThemeColor::from_rgb8(220, 220, 220),
+    view: ThemeColor::from_rgb8(35, 37, 38),
+    sidebar: ThemeColor::from_rgb8(38, 41, 42),
+    headerbar: ThemeColor::from_rgb8(51, 54, 56),
+    card: ThemeColor::from_rgb8(51, 55, 56),
+    border: ThemeColor::from_rgb8(28, 30, 31),
+    muted_foreground: ThemeColor::from_rgb8(160, 160, 160),
+    disabled_foreground: ThemeColor::from_rgb8(110, 110, 110),
+    accent: ThemeColor::from_rgb8(42, 118, 198),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(42, 118, 198)),
+    accent_foreground: ThemeColor::WHITE,
+    selection: ThemeColor::from_rgb8(42, 118, 198),
+    selection_foreground: ThemeColor::WHITE,
+    focus_ring: ThemeColor::from_rgb8(42, 118, 198),
+    link: ThemeColor::from_rgb8(77, 157, 235),
+    visited_link: ThemeColor::from_rgb8(180, 140, 220),
+    tooltip: ThemeColor::from_rgb8(20, 20, 20),
+    tooltip_foreground: ThemeColor::from_rgb8(220, 220, 220),
+    success: ThemeColor::from_rgb8(115, 210, 22),
+    warning: ThemeColor::from_rgb8(252, 175, 62),
+    danger: ThemeColor::from_rgb8(239, 41, 41),
+};
+
+/// Ubuntu Yaru light theme palette
+///
+/// Source: https://github.com/ubuntu/yaru/blob/master/common/_palette.scss
+pub const YARU_LIGHT: ThemePalette = ThemePalette {
+    background: ThemeColor::from_rgb8(250, 250, 250),
+    foreground: ThemeColor::from_rgb8(61, 61, 61),
+    view: ThemeColor::WHITE,
+    sidebar: ThemeColor::from_rgb8(242, 242, 242),
+    headerbar: ThemeColor::from_rgb8(235, 235, 235),
+    card: ThemeColor::WHITE,
+    border: ThemeColor::from_rgb8(216, 216, 216),
+    muted_foreground: ThemeColor::from_rgb8(118, 118, 118),
+    disabled_foreground: ThemeColor::from_rgb8(156, 156, 156),
+    accent: ThemeColor::from_rgb8(233, 84, 32),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(233, 84, 32)),
+    accent_foreground: ThemeColor::WHITE,
+    selection: ThemeColor::from_rgb8(233, 84, 32),
+    selection_foreground: ThemeColor::WHITE,
+    focus_ring: ThemeColor::from_rgb8(233, 84, 32),
+    link: ThemeColor::from_rgb8(0, 115, 229),
+    visited_link: ThemeColor::from_rgb8(118, 37, 114),
+    tooltip: ThemeColor::from_rgb8(29, 29, 29),
+    tooltip_foreground: ThemeColor::WHITE,
+    success: ThemeColor::from_rgb8(62, 179, 79),
+    warning: ThemeColor::from_rgb8(249, 155, 17),
+    danger: ThemeColor::from_rgb8(199, 22, 43),
+};
+
+/// Ubuntu Yaru dark theme palette
+///
+/// Source: https://github.com/ubuntu/yaru/blob/master/common/_palette.scss
+pub const YARU_DARK: ThemePalette = ThemePalette {
+    background: ThemeColor::from_rgb8(44, 44, 44),
+    foreground: ThemeColor::from_rgb8(247, 247, 247),
+    view: ThemeColor::from_rgb8(30, 30, 30),
+    sidebar: ThemeColor::from_rgb8(40, 40, 40),
+    headerbar: ThemeColor::from_rgb8(34, 34, 34),
+    card: ThemeColor::from_rgb8(52, 52, 52),
+    border: ThemeColor::from_rgb8(27, 27, 27),
+    muted_foreground: ThemeColor::from_rgb8(186, 186, 186),
+    disabled_foreground: ThemeColor::from_rgb8(146, 146, 146),
+    accent: ThemeColor::from_rgb8(233, 84, 32),
+    accent_shades: ThemeAccentShades::from_accent(ThemeColor::from_rgb8(233, 84, 32)),
+    accent_foreground: ThemeColor::WHITE,
+    selection: ThemeColor::from_rgb8(233, 84, 32),
+    selection_foreground: ThemeColor::WHITE,
+    focus_ring: ThemeColor::from_rgb8(233, 84, 32),
+    link: ThemeColor::from_rgb8(51, 153, 255),
+    visited_link: ThemeColor::from_rgb8(185, 120, 180),
+    tooltip: ThemeColor::from_rgb8(24, 24, 24),
+    tooltip_foreground: ThemeColor::WHITE,
+    success: ThemeColor::from_rgb8(62, 179, 79),
+    warning: ThemeColor::from_rgb8(249, 155, 17),
+    danger: ThemeColor::from_rgb8(239, 41, 41),
+};
+
+/// Accent color shades (e.g. for hover, pressed and selection states)
+///
+/// Shades are ordered from the closest to the accent color to the furthest.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThemeAccentShades {
+    /// Slightly lighter accent color
+    pub light1: ThemeColor,
+    /// Lighter accent color
+    pub light2: ThemeColor,
+    /// Much lighter accent color
+    pub light3: ThemeColor,
+    /// Slightly darker accent color
+    pub dark1: ThemeColor,
+    /// Darker accent color
+    pub dark2: ThemeColor,
+    /// Much darker accent color
+    pub dark3: ThemeColor,
+}
+
+impl ThemeAccentShades {
+    /// Compute the shades of an accent color.
+    ///
+    /// The accent is mixed with white or black in fixed steps. This is only an approximation of
+    /// the system shades: Windows also shifts their hue and chroma (e.g. its lightest shade of
+    /// `#0078D4` is `#99EBFF`, where this gives `#B3D7F2`), so the shades reported by the
+    /// platform should be preferred when available.
+    pub const fn from_accent(accent: ThemeColor) -> Self {
+        let [step1, step2, step3] = ACCENT_SHADE_STEPS;
+
+        Self {
+            light1: accent.mix(ThemeColor::WHITE, step1),
+            light2: accent.mix(ThemeColor::WHITE, step2),
+            light3: accent.mix(ThemeColor::WHITE, step3),
+            dark1: accent.mix(ThemeColor::BLACK, step1),
+            dark2: accent.mix(ThemeColor::BLACK, step2),
+            dark3: accent.mix(ThemeColor::BLACK, step3),
+        }
+    }
+
+    /// Get the shades with their names, in the order of `SHADES`.
+    pub(crate) fn shades(&self) -> [(&'static str, ThemeColor); SHADES.len()] {
+        [
+            ("light1", self.light1),
+            ("light2", self.light2),
+            ("light3", self.light3),
+            ("dark1", self.dark1),
+            ("dark2", self.dark2),
+            ("dark3", self.dark3),
+        ]
+    }
+
+    /// Get a shade by its name (as serialized).
+    pub(crate) fn shade_mut(&mut self, name: &str) -> Option<&mut ThemeColor> {
+        let shade = match name {
+            "light1" => &mut self.light1,
+            "light2" => &mut self.light2,
+            "light3" => &mut self.light3,
+            "dark1" => &mut self.dark1,
+            "dark2" => &mut self.dark2,
+            "dark3" => &mut self.dark3,
+            _ => return None,
+        };
+
+        Some(shade)
+    }
+}
+
 /// Theme Palette
+///
+/// With the `serde` feature, roles missing when deserializing are taken from the default palette
+/// of the matching scheme (guessed from the background), and accent shades are computed from the
+/// accent color.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ThemePalette {
-    /// Background color
+    /// Window background color
     pub background: ThemeColor,
     /// Foreground color
     pub foreground: ThemeColor,
+    /// Background color of content views (e.g. lists, text fields and documents)
+    pub view: ThemeColor,
+    /// Sidebar background color
+    pub sidebar: ThemeColor,
+    /// Header bar (and title bar) background color
+    pub headerbar: ThemeColor,
+    /// Card background color
+    pub card: ThemeColor,
+    /// Border and separator color
+    pub border: ThemeColor,
+    /// Secondary text color (e.g. captions and placeholders)
+    pub muted_foreground: ThemeColor,
+    /// Text color of disabled elements
+    pub disabled_foreground: ThemeColor,
     /// Accent color
     pub accent: ThemeColor,
+    /// Lighter and darker variants of the accent color
+    pub accent_shades: ThemeAccentShades,
+    /// Text color used on top of the accent color
+    pub accent_foreground: ThemeColor,
+    /// Background color of selected items and text
+    pub selection: ThemeColor,
+    /// Text color of selected items and text
+    pub selection_foreground: ThemeColor,
+    /// Keyboard focus indicator color
+    pub focus_ring: ThemeColor,
+    /// Link color
+    pub link: ThemeColor,
+    /// Visited link color
+    pub visited_link: ThemeColor,
+    /// Tooltip background color
+    pub tooltip: ThemeColor,
+    /// Tooltip text color
+    pub tooltip_foreground: ThemeColor,
     /// Success color
     pub success: ThemeColor,
     /// Warning color
@@ -114,3 +908,311 @@ pub struct ThemePalette {
     /// Danger color
     pub danger: ThemeColor,
 }
+
+impl ThemePalette {
+    /// Get the high contrast variant of the palette (for themes without a dedicated one).
+    ///
+    /// Background and foreground become pure white and black, other colors are moved toward the
+    /// foreground as needed.
+    pub(crate) fn with_high_contrast(self, scheme: ThemeScheme) -> Self {
+        let (background, foreground) = match scheme {
+            ThemeScheme::Light => (ThemeColor::WHITE, ThemeColor::BLACK),
+            ThemeScheme::Dark => (ThemeColor::BLACK, ThemeColor::WHITE),
+        };
+        let tune = |color: ThemeColor| with_high_contrast(color, background, foreground);
+        let accent = tune(self.accent);
+
+        // Surfaces are merged into the background, borders use the foreground
+        Self {
+            background,
+            foreground,
+            view: background,
+            sidebar: background,
+            headerbar: background,
+            card: background,
+            border: foreground,
+            muted_foreground: tune(self.muted_foreground),
+            disabled_foreground: tune(self.disabled_foreground),
+            accent,
+            accent_shades: ThemeAccentShades::from_accent(accent),
+            // Tuned colors have high contrast against the background
+            accent_foreground: background,
+            selection: accent,
+            selection_foreground: background,
+            focus_ring: accent,
+            link: tune(self.link),
+            visited_link: tune(self.visited_link),
+            tooltip: background,
+            tooltip_foreground: foreground,
+            success: tune(self.success),
+            warning: tune(self.warning),
+            danger: tune(self.danger),
+        }
+    }
+
+    /// Get the reduced contrast variant of the palette.
+    ///
+    /// Background is kept, other colors are moved toward it. Text on selections and tooltips is
+    /// moved toward their own background.
+    pub(crate) fn with_reduced_contrast(self) -> Self {
+        let tune = |color: ThemeColor| color.mix(self.background, REDUCED_CONTRAST_MIX);
+        let accent = tune(self.accent);
+        let selection = tune(self.selection);
+        let tooltip = tune(self.tooltip);
+
+        Self {
+            background: self.background,
+            foreground: tune(self.foreground),
+            view: tune(self.view),
+            sidebar: tune(self.sidebar),
+            headerbar: tune(self.headerbar),
+            card: tune(self.card),
+            border: tune(self.border),
+            muted_foreground: tune(self.muted_foreground),
+            disabled_foreground: tune(self.disabled_foreground),
+            accent,
+            accent_shades: ThemeAccentShades::from_accent(accent),
+            accent_foreground: tune(self.accent_foreground),
+            selection,
+            selection_foreground: self
+                .selection_foreground
+                .mix(selection, REDUCED_CONTRAST_MIX),
+            focus_ring: tune(self.focus_ring),
+            link: tune(self.link),
+            visited_link: tune(self.visited_link),
+            tooltip,
+            tooltip_foreground: self.tooltip_foreground.mix(tooltip, REDUCED_CONTRAST_MIX),
+            success: tune(self.success),
+            warning: tune(self.warning),
+            danger: tune(self.danger),
+        }
+    }
+
+    /// Get the default palette for a background color, using its scheme.
+    pub(crate) fn default_for_background(background: ThemeColor) -> Self {
+        crate::Theme::new(
+            ThemeKind::default(),
+            background_scheme(background),
+            ThemeContrast::Normal,
+            None,
+        )
+        .palette
+    }
+
+    /// Get the scheme of the palette, guessed from the background.
+    pub(crate) fn scheme(&self) -> ThemeScheme {
+        background_scheme(self.background)
+    }
+
+    /// Build a palette from some of its roles (by name).
+    ///
+    /// Missing roles are taken from the default palette of the matching scheme (guessed from the
+    /// background), except the ones derived from a custom accent (shades, selection, etc.).
+    pub(crate) fn from_roles(roles: &[(&str, ThemeColor)]) -> Self {
+        let role = |name: &str| {
+            roles
+                .iter()
+                .find(|(role, _)| *role == name)
+                .map(|(_, color)| *color)
+        };
+        let mut palette =
+            Self::default_for_background(role("background").unwrap_or(ThemeColor::WHITE));
+
+        // Selection depends on the view and foreground, explicit roles override derived ones
+        for name in ["foreground", "view"] {
+            if let (Some(color), Some(slot)) = (role(name), palette.role_mut(name)) {
+                *slot = color;
+            }
+        }
+        if let Some(accent) = role("accent") {
+            palette.set_accent(accent);
+        }
+        for (name, color) in roles {
+            if let Some(slot) = palette.role_mut(name) {
+                *slot = *color;
+            }
+        }
+
+        palette
+    }
+
+    /// Get the color roles with their names, in the order of `ROLES`.
+    pub(crate) fn roles(&self) -> [(&'static str, ThemeColor); ROLES.len()] {
+        [
+            ("background", self.background),
+            ("foreground", self.foreground),
+            ("view", self.view),
+            ("sidebar", self.sidebar),
+            ("headerbar", self.headerbar),
+            ("card", self.card),
+            ("border", self.border),
+            ("muted_foreground", self.muted_foreground),
+            ("disabled_foreground", self.disabled_foreground),
+            ("accent", self.accent),
+            ("accent_foreground", self.accent_foreground),
+            ("selection", self.selection),
+            ("selection_foreground", self.selection_foreground),
+            ("focus_ring", self.focus_ring),
+            ("link", self.link),
+            ("visited_link", self.visited_link),
+            ("tooltip", self.tooltip),
+            ("tooltip_foreground", self.tooltip_foreground),
+            ("success", self.success),
+            ("warning", self.warning),
+            ("danger", self.danger),
+        ]
+    }
+
+    /// Get a mutable reference to a color role by name (see `ROLES`).
+    pub(crate) fn role_mut(&mut self, name: &str) -> Option<&mut ThemeColor> {
+        let role = match name {
+            "background" => &mut self.background,
+            "foreground" => &mut self.foreground,
+            "view" => &mut self.view,
+            "sidebar" => &mut self.sidebar,
+            "headerbar" => &mut self.headerbar,
+            "card" => &mut self.card,
+            "border" => &mut self.border,
+            "muted_foreground" => &mut self.muted_foreground,
+            "disabled_foreground" => &mut self.disabled_foreground,
+            "accent" => &mut self.accent,
+            "accent_foreground" => &mut self.accent_foreground,
+            "selection" => &mut self.selection,
+            "selection_foreground" => &mut self.selection_foreground,
+            "focus_ring" => &mut self.focus_ring,
+            "link" => &mut self.link,
+            "visited_link" => &mut self.visited_link,
+            "tooltip" => &mut self.tooltip,
+            "tooltip_foreground" => &mut self.tooltip_foreground,
+            "success" => &mut self.success,
+            "warning" => &mut self.warning,
+            "danger" => &mut self.danger,
+            _ => return None,
+        };
+
+        Some(role)
+    }
+
+    /// Set the accent color, computing its shades and the text color used on it.
+    ///
+    /// Selection follows the accent, as a solid color if it was the accent color before, or as a
+    /// tint of the view background otherwise. The focus ring follows it if it was the accent color.
+    pub fn set_accent(&mut self, accent: ThemeColor) {
+        if self.selection == self.accent {
+            self.selection = accent;
+            self.selection_foreground = accent.text_color();
+        } else {
+            self.selection = tinted_selection(accent, self.view);
+            self.selection_foreground = self.foreground;
+        }
+        if self.focus_ring == self.accent {
+            self.focus_ring = accent;
+        }
+
+        self.accent = accent;
+        self.accent_shades = ThemeAccentShades::from_accent(accent);
+        self.accent_foreground = accent.text_color();
+    }
+
+    /// Adjust a custom accent color to the contrast level of the palette.
+    pub(crate) fn adjust_accent(&self, accent: ThemeColor, contrast: ThemeContrast) -> ThemeColor {
+        match contrast {
+            ThemeContrast::High => with_high_contrast(accent, self.background, self.foreground),
+            ThemeContrast::Reduced => accent.mix(self.background, REDUCED_CONTRAST_MIX),
+            // Forced colors are used as is
+            _ => accent,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn high_contrast_palettes() {
+        let kinds = [
+            ThemeKind::Windows,
+            ThemeKind::MacOS,
+            ThemeKind::Gtk,
+            ThemeKind::Qt,
+        ];
+
+        for kind in kinds {
+            for scheme in [ThemeScheme::Light, ThemeScheme::Dark] {
+                let palette = high_contrast_palette(kind, scheme).unwrap();
+                let colors = [
+                    ("foreground", palette.foreground),
+                    ("border", palette.border),
+                    ("muted_foreground", palette.muted_foreground),
+                    ("accent", palette.accent),
+                    ("focus_ring", palette.focus_ring),
+                    ("link", palette.link),
+                    ("visited_link", palette.visited_link),
+                    ("success", palette.success),
+                    ("warning", palette.warning),
+                    ("danger", palette.danger),
+                ];
+
+                for (role, color) in colors {
+                    let ratio = color.contrast_ratio(palette.background);
+                    assert!(
+                        ratio >= HIGH_CONTRAST_RATIO,
+                        "{:?} {:?} {} is {}:1",
+                        kind,
+                        scheme,
+                        role,
+                        ratio
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn accent_shades() {
+        let accent = ThemeColor::from_rgb8(0, 120, 212);
+        let shades = ThemeAccentShades::from_accent(accent);
+
+        let expected = [
+            ("light1", "#409ADF"),
+            ("light2", "#73B5E7"),
+            ("light3", "#B3D7F2"),
+            ("dark1", "#005A9F"),
+            ("dark2", "#004275"),
+            ("dark3", "#002440"),
+        ];
+        for ((name, shade), (expected_name, expected)) in shades.shades().into_iter().zip(expected)
+        {
+            assert_eq!(name, expected_name);
+            assert_eq!(shade.to_string(), expected, "{}", name);
+        }
+
+        // Shades get further from the accent
+        let ordered = [
+            shades.dark3,
+            shades.dark2,
+            shades.dark1,
+            accent,
+            shades.light1,
+            shades.light2,
+            shades.light3,
+        ];
+        for pair in ordered.windows(2) {
+            assert!(pair[0].relative_luminance() < pair[1].relative_luminance());
+        }
+    }
+
+    #[test]
+    fn pantheon_dark_text_colors() {
+        // Accent of the dark variant is light, so it needs dark text
+        assert_eq!(
+            PANTHEON_DARK.accent_foreground,
+            PANTHEON_DARK.accent.text_color()
+        );
+        assert_eq!(
+            PANTHEON_DARK.selection_foreground,
+            PANTHEON_DARK.selection.text_color()
+        );
+    }
+}
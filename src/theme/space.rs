@@ -0,0 +1,477 @@
+//! Color spaces
+//!
+//! Hues are in degrees (0.0 - 360.0), other components are in 0.0 - 1.0 unless stated otherwise.
+use crate::ThemeColor;
+
+/// Iterations used to find the largest chroma that fits in sRGB
+const GAMUT_MAPPING_STEPS: usize = 16;
+/// Tolerance for a component to be considered in the sRGB gamut (absorbs rounding errors)
+const GAMUT_EPSILON: f32 = 1e-4;
+
+/// Color in linear sRGB (gamma decoded, used to blend light)
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinearRgb {
+    /// Red component
+    pub red: f32,
+    /// Green component
+    pub green: f32,
+    /// Blue component
+    pub blue: f32,
+    /// Alpha component
+    pub alpha: f32,
+}
+
+/// Color in HSL (hue, saturation, lightness)
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hsl {
+    /// Hue (in degrees)
+    pub hue: f32,
+    /// Saturation
+    pub saturation: f32,
+    /// Lightness
+    pub lightness: f32,
+    /// Alpha component
+    pub alpha: f32,
+}
+
+/// Color in HSV (hue, saturation, value)
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hsv {
+    /// Hue (in degrees)
+    pub hue: f32,
+    /// Saturation
+    pub saturation: f32,
+    /// Value (brightness)
+    pub value: f32,
+    /// Alpha component
+    pub alpha: f32,
+}
+
+/// Color in OKLab (perceptual lightness and opponent axes)
+///
+/// Source: https://bottosson.github.io/posts/oklab/
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Oklab {
+    /// Perceptual lightness
+    pub lightness: f32,
+    /// Green (negative) to red (positive) axis (about -0.4 - 0.4)
+    pub a: f32,
+    /// Blue (negative) to yellow (positive) axis (about -0.4 - 0.4)
+    pub b: f32,
+    /// Alpha component
+    pub alpha: f32,
+}
+
+/// Color in OKLCH (polar form of OKLab)
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Oklch {
+    /// Perceptual lightness
+    pub lightness: f32,
+    /// Chroma (0.0 is gray, sRGB colors stay below 0.4)
+    pub chroma: f32,
+    /// Hue (in degrees)
+    pub hue: f32,
+    /// Alpha component
+    pub alpha: f32,
+}
+
+/// Decode a gamma encoded sRGB component (mirrored for negative values, as done by CSS)
+fn to_linear(component: f32) -> f32 {
+    let magnitude = component.abs();
+    if magnitude <= 0.04045 {
+        component / 12.92
+    } else {
+        ((magnitude + 0.055) / 1.055).powf(2.4).copysign(component)
+    }
+}
+
+/// Encode a linear sRGB component (mirrored for negative values, as done by CSS)
+fn from_linear(component: f32) -> f32 {
+    let magnitude = component.abs();
+    if magnitude <= 0.0031308 {
+        component * 12.92
+    } else {
+        (1.055 * magnitude.powf(1.0 / 2.4) - 0.055).copysign(component)
+    }
+}
+
+/// Get the hue (in degrees) of a RGB color, with its maximum component and chroma
+fn rgb_hue(red: f32, green: f32, blue: f32, max: f32, chroma: f32) -> f32 {
+    // Gray colors have no hue
+    if chroma <= 0.0 {
+        return 0.0;
+    }
+
+    let hue = if max == red {
+        (green - blue) / chroma
+    } else if max == green {
+        (blue - red) / chroma + 2.0
+    } else {
+        (red - green) / chroma + 4.0
+    };
+
+    (hue * 60.0).rem_euclid(360.0)
+}
+
+/// Get the RGB components of a hue, with the chroma and the maximum component
+fn hue_rgb(hue: f32, chroma: f32, max: f32) -> (f32, f32, f32) {
+    let component = |shift: f32| {
+        let sector = (hue / 60.0 + shift).rem_euclid(6.0);
+        max - chroma * sector.min(4.0 - sector).clamp(0.0, 1.0)
+    };
+
+    (component(5.0), component(3.0), component(1.0))
+}
+
+/// Check if every component is in the sRGB gamut
+fn in_gamut(color: ThemeColor) -> bool {
+    [color.red, color.green, color.blue]
+        .iter()
+        .all(|component| (-GAMUT_EPSILON..=1.0 + GAMUT_EPSILON).contains(component))
+}
+
+/// Clamp the components to the sRGB gamut
+fn clip(color: ThemeColor) -> ThemeColor {
+    ThemeColor::from_rgba(
+        color.red.clamp(0.0, 1.0),
+        color.green.clamp(0.0, 1.0),
+        color.blue.clamp(0.0, 1.0),
+        color.alpha.clamp(0.0, 1.0),
+    )
+}
+
+impl ThemeColor {
+    /// Convert to linear sRGB.
+    pub fn to_linear_rgb(self) -> LinearRgb {
+        LinearRgb {
+            red: to_linear(self.red),
+            green: to_linear(self.green),
+            blue: to_linear(self.blue),
+            alpha: self.alpha,
+        }
+    }
+
+    /// Convert from linear sRGB.
+    pub fn from_linear_rgb(color: LinearRgb) -> Self {
+        Self::from_rgba(
+            from_linear(color.red),
+            from_linear(color.green),
+            from_linear(color.blue),
+            color.alpha,
+        )
+    }
+
+    /// Convert to HSL.
+    pub fn to_hsl(self) -> Hsl {
+        let max = self.red.max(self.green).max(self.blue);
+        let min = self.red.min(self.green).min(self.blue);
+        let chroma = max - min;
+        let lightness = (max + min) / 2.0;
+
+        let saturation = if lightness <= 0.0 || lightness >= 1.0 {
+            0.0
+        } else {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+
+        Hsl {
+            hue: rgb_hue(self.red, self.green, self.blue, max, chroma),
+            saturation,
+            lightness,
+            alpha: self.alpha,
+        }
+    }
+
+    /// Convert from HSL.
+    pub fn from_hsl(color: Hsl) -> Self {
+        let chroma = (1.0 - (2.0 * color.lightness - 1.0).abs()) * color.saturation;
+        let (red, green, blue) = hue_rgb(color.hue, chroma, color.lightness + chroma / 2.0);
+
+        Self::from_rgba(red, green, blue, color.alpha)
+    }
+
+    /// Convert to HSV.
+    pub fn to_hsv(self) -> Hsv {
+        let max = self.red.max(self.green).max(self.blue);
+        let min = self.red.min(self.green).min(self.blue);
+        let chroma = max - min;
+
+        Hsv {
+            hue: rgb_hue(self.red, self.green, self.blue, max, chroma),
+            saturation: if max <= 0.0 { 0.0 } else { chroma / max },
+            value: max,
+            alpha: self.alpha,
+        }
+    }
+
+    /// Convert from HSV.
+    pub fn from_hsv(color: Hsv) -> Self {
+        let chroma = color.value * color.saturation;
+        let (red, green, blue) = hue_rgb(color.hue, chroma, color.value);
+
+        Self::from_rgba(red, green, blue, color.alpha)
+    }
+
+    /// Convert to OKLab.
+    pub fn to_oklab(self) -> Oklab {
+        let color = self.to_linear_rgb();
+        // Matrices are computed in double precision, as published
+        let (red, green, blue) = (color.red as f64, color.green as f64, color.blue as f64);
+
+        let l = (0.4122214708 * red + 0.5363325363 * green + 0.0514459929 * blue).cbrt();
+        let m = (0.2119034982 * red + 0.6806995451 * green + 0.1073969566 * blue).cbrt();
+        let s = (0.0883024619 * red + 0.2817188376 * green + 0.6299787005 * blue).cbrt();
+
+        Oklab {
+            lightness: (0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s) as f32,
+            a: (1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s) as f32,
+            b: (0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s) as f32,
+            alpha: color.alpha,
+        }
+    }
+
+    /// Convert from OKLab.
+    ///
+    /// Colors outside of the sRGB gamut are not clipped.
+    pub fn from_oklab(color: Oklab) -> Self {
+        let (lightness, a, b) = (color.lightness as f64, color.a as f64, color.b as f64);
+
+        let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+        let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+        let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+        Self::from_linear_rgb(LinearRgb {
+            red: (4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s) as f32,
+            green: (-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s) as f32,
+            blue: (-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s) as f32,
+            alpha: color.alpha,
+        })
+    }
+
+    /// Convert to OKLCH.
+    pub fn to_oklch(self) -> Oklch {
+        let Oklab {
+            lightness,
+            a,
+            b,
+            alpha,
+        } = self.to_oklab();
+
+        Oklch {
+            lightness,
+            chroma: a.hypot(b),
+            hue: b.atan2(a).to_degrees().rem_euclid(360.0),
+            alpha,
+        }
+    }
+
+    /// Convert from OKLCH.
+    ///
+    /// Colors outside of the sRGB gamut are not clipped.
+    pub fn from_oklch(color: Oklch) -> Self {
+        let (sin, cos) = color.hue.to_radians().sin_cos();
+
+        Self::from_oklab(Oklab {
+            lightness: color.lightness,
+            a: color.chroma * cos,
+            b: color.chroma * sin,
+            alpha: color.alpha,
+        })
+    }
+
+    /// Convert from OKLCH, reducing the chroma until the color fits in sRGB.
+    ///
+    /// Lightness and hue are kept, so the color keeps its perceived brightness and tint.
+    pub fn from_oklch_mapped(color: Oklch) -> Self {
+        let lightness = color.lightness.clamp(0.0, 1.0);
+        let color = Oklch { lightness, ..color };
+
+        let exact = Self::from_oklch(color);
+        if in_gamut(exact) {
+            return clip(exact);
+        }
+
+        // Binary search of the largest chroma in the gamut
+        let (mut low, mut high) = (0.0, color.chroma.max(0.0));
+        for _ in 0..GAMUT_MAPPING_STEPS {
+            let chroma = (low + high) / 2.0;
+            if in_gamut(Self::from_oklch(Oklch { chroma, ..color })) {
+                low = chroma;
+            } else {
+                high = chroma;
+            }
+        }
+
+        clip(Self::from_oklch(Oklch {
+            chroma: low,
+            ..color
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32, tolerance: f32) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{} is not {} (+/- {})",
+            actual,
+            expected,
+            tolerance
+        );
+    }
+
+    #[test]
+    fn oklab_reference_values() {
+        // sRGB values published with OKLab by Björn Ottosson
+        let pairs = [
+            (ThemeColor::WHITE, (1.0, 0.0, 0.0)),
+            (ThemeColor::BLACK, (0.0, 0.0, 0.0)),
+            (
+                ThemeColor::from_rgb8(255, 0, 0),
+                (0.627955, 0.224863, 0.125846),
+            ),
+            (
+                ThemeColor::from_rgb8(0, 255, 0),
+                (0.866440, -0.233888, 0.179498),
+            ),
+            (
+                ThemeColor::from_rgb8(0, 0, 255),
+                (0.452014, -0.032457, -0.311528),
+            ),
+        ];
+
+        for (color, (lightness, a, b)) in pairs {
+            let oklab = color.to_oklab();
+            assert_close(oklab.lightness, lightness, 1e-4);
+            assert_close(oklab.a, a, 1e-4);
+            assert_close(oklab.b, b, 1e-4);
+
+            let back = ThemeColor::from_oklab(oklab);
+            assert_eq!(back.to_string(), color.to_string());
+        }
+    }
+
+    #[test]
+    fn oklch_reference_values() {
+        let pairs = [
+            (
+                ThemeColor::from_rgb8(255, 0, 0),
+                (0.627955, 0.257683, 29.2339),
+            ),
+            (
+                ThemeColor::from_rgb8(0, 255, 0),
+                (0.866440, 0.294827, 142.4953),
+            ),
+            (
+                ThemeColor::from_rgb8(0, 0, 255),
+                (0.452014, 0.313214, 264.0521),
+            ),
+        ];
+
+        for (color, (lightness, chroma, hue)) in pairs {
+            let oklch = color.to_oklch();
+            assert_close(oklch.lightness, lightness, 1e-4);
+            assert_close(oklch.chroma, chroma, 1e-4);
+            assert_close(oklch.hue, hue, 1e-2);
+
+            let back = ThemeColor::from_oklch(oklch);
+            assert_eq!(back.to_string(), color.to_string());
+        }
+    }
+
+    #[test]
+    fn hsl_hsv_primaries() {
+        let primaries = [
+            (ThemeColor::from_rgb8(255, 0, 0), 0.0),
+            (ThemeColor::from_rgb8(0, 255, 0), 120.0),
+            (ThemeColor::from_rgb8(0, 0, 255), 240.0),
+        ];
+
+        for (color, hue) in primaries {
+            let hsl = color.to_hsl();
+            assert_close(hsl.hue, hue, 1e-4);
+            assert_close(hsl.saturation, 1.0, 1e-6);
+            assert_close(hsl.lightness, 0.5, 1e-6);
+            assert_eq!(ThemeColor::from_hsl(hsl), color);
+
+            let hsv = color.to_hsv();
+            assert_close(hsv.hue, hue, 1e-4);
+            assert_close(hsv.saturation, 1.0, 1e-6);
+            assert_close(hsv.value, 1.0, 1e-6);
+            assert_eq!(ThemeColor::from_hsv(hsv), color);
+        }
+    }
+
+    #[test]
+    fn hsl_hsv_gray() {
+        let gray = ThemeColor::from_rgb8(128, 128, 128);
+
+        let hsl = gray.to_hsl();
+        assert_eq!(hsl.hue, 0.0);
+        assert_eq!(hsl.saturation, 0.0);
+        assert_close(hsl.lightness, 128.0 / 255.0, 1e-6);
+        assert_eq!(ThemeColor::from_hsl(hsl), gray);
+
+        let hsv = gray.to_hsv();
+        assert_eq!(hsv.hue, 0.0);
+        assert_eq!(hsv.saturation, 0.0);
+        assert_close(hsv.value, 128.0 / 255.0, 1e-6);
+        assert_eq!(ThemeColor::from_hsv(hsv), gray);
+    }
+
+    #[test]
+    fn linear_round_trip() {
+        // Around the breakpoints of both transfer functions
+        let components = [
+            0.0, 0.003, 0.0031308, 0.0032, 0.04, 0.04045, 0.0405, 0.05, 0.5, 1.0,
+        ];
+
+        for component in components {
+            assert_close(from_linear(to_linear(component)), component, 1e-6);
+            assert_close(to_linear(from_linear(component)), component, 1e-6);
+            assert_close(from_linear(to_linear(-component)), -component, 1e-6);
+        }
+
+        // Both pieces meet at the breakpoint
+        assert_close(to_linear(0.04045), 0.0031308, 1e-6);
+        assert_close(from_linear(0.0031308), 0.04045, 1e-5);
+        assert_close(to_linear(0.5), 0.214041, 1e-6);
+    }
+
+    #[test]
+    fn oklch_mapped_in_gamut() {
+        for hue in (0..360).step_by(15) {
+            for lightness in [0.0, 0.2, 0.5, 0.7, 0.9, 1.0] {
+                let oklch = Oklch {
+                    lightness,
+                    chroma: 0.4,
+                    hue: hue as f32,
+                    alpha: 1.0,
+                };
+                let color = ThemeColor::from_oklch_mapped(oklch);
+                assert!(
+                    [color.red, color.green, color.blue]
+                        .iter()
+                        .all(|component| (0.0..=1.0).contains(component)),
+                    "{:?} is out of gamut",
+                    color
+                );
+
+                // Hue is only meaningful for colors that kept some chroma
+                let mapped = color.to_oklch();
+                if mapped.chroma > 0.02 {
+                    let difference = (mapped.hue - oklch.hue + 180.0).rem_euclid(360.0) - 180.0;
+                    assert_close(difference, 0.0, 1.0);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,410 @@
+//! Tonal palettes generated from a seed color (in the style of Material 3)
+//!
+//! Material uses the HCT color space, here hue and chroma come from OKLCH while tones are CIELAB
+//! lightness (0 is black, 100 is white), so contrast between two tones is predictable.
+use crate::{Oklch, ThemeAccentShades, ThemeColor, ThemePalette, ThemeScheme};
+
+/// Chroma of the other palettes relative to the primary one (Material 3 tonal spot proportions)
+const SECONDARY_CHROMA: f32 = 16.0 / 36.0;
+const TERTIARY_CHROMA: f32 = 24.0 / 36.0;
+const NEUTRAL_CHROMA: f32 = 6.0 / 36.0;
+const NEUTRAL_VARIANT_CHROMA: f32 = 8.0 / 36.0;
+/// Hue rotation of the tertiary palette (in degrees)
+const TERTIARY_HUE_SHIFT: f32 = 60.0;
+
+/// Maximum hue rotation when harmonizing a color (in degrees)
+const HARMONIZE_MAX_ROTATION: f32 = 15.0;
+/// Chroma below which a color is considered gray (its hue is meaningless)
+const ACHROMATIC_CHROMA: f32 = 0.01;
+/// Status colors, harmonized with the seed
+const SUCCESS_SOURCE: ThemeColor = ThemeColor::from_rgb8(56, 142, 60);
+const WARNING_SOURCE: ThemeColor = ThemeColor::from_rgb8(230, 126, 0);
+const DANGER_SOURCE: ThemeColor = ThemeColor::from_rgb8(179, 38, 30);
+
+/// Opacity of disabled text (Material 3 uses 38%)
+const DISABLED_OPACITY: f32 = 0.38;
+
+/// Iterations used to find the OKLCH lightness of a tone
+const TONE_SEARCH_STEPS: usize = 20;
+
+/// Convert a tone (CIELAB lightness, 0 - 100) to a relative luminance
+fn tone_to_luminance(tone: f32) -> f32 {
+    let tone = tone.clamp(0.0, 100.0);
+    if tone > 8.0 {
+        ((tone + 16.0) / 116.0).powi(3)
+    } else {
+        tone / 903.2963
+    }
+}
+
+/// Tonal palette, every tone of a hue and chroma
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TonalPalette {
+    /// Hue (OKLCH, in degrees)
+    pub hue: f32,
+    /// Chroma (OKLCH), reduced at tones that can't show it in sRGB
+    pub chroma: f32,
+}
+
+impl TonalPalette {
+    /// Create a tonal palette from a hue and chroma (OKLCH).
+    pub fn new(hue: f32, chroma: f32) -> Self {
+        Self {
+            hue: hue.rem_euclid(360.0),
+            chroma: chroma.max(0.0),
+        }
+    }
+
+    /// Create the tonal palette of a color (using its hue and chroma).
+    pub fn from_color(color: ThemeColor) -> Self {
+        let color = color.to_oklch();
+        Self::new(color.hue, color.chroma)
+    }
+
+    /// Get the color at a tone (CIELAB lightness, 0 is black and 100 is white).
+    pub fn tone(&self, tone: f32) -> ThemeColor {
+        let luminance = tone_to_luminance(tone);
+        let color = |lightness: f32| {
+            ThemeColor::from_oklch_mapped(Oklch {
+                lightness,
+                chroma: self.chroma,
+                hue: self.hue,
+                alpha: 1.0,
+            })
+        };
+
+        // Luminance grows with the OKLCH lightness, search the one that matches the tone
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..TONE_SEARCH_STEPS {
+            let lightness = (low + high) / 2.0;
+            if color(lightness).relative_luminance() < luminance {
+                low = lightness;
+            } else {
+                high = lightness;
+            }
+        }
+
+        color((low + high) / 2.0)
+    }
+}
+
+/// Tonal palettes of a color scheme, generated from a seed color
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TonalScheme {
+    /// Primary palette (the seed hue and chroma)
+    pub primary: TonalPalette,
+    /// Secondary palette (the seed hue, less colorful)
+    pub secondary: TonalPalette,
+    /// Tertiary palette (rotated hue, for contrasting accents)
+    pub tertiary: TonalPalette,
+    /// Neutral palette (backgrounds and text)
+    pub neutral: TonalPalette,
+    /// Neutral variant palette (outlines and secondary surfaces)
+    pub neutral_variant: TonalPalette,
+}
+
+/// Color roles of a tonal scheme
+///
+/// `on_*` roles are the text colors used on top of the matching role.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TonalRoles {
+    /// Primary color (main accent)
+    pub primary: ThemeColor,
+    /// Text on the primary color
+    pub on_primary: ThemeColor,
+    /// Primary container (less prominent accent fills)
+    pub primary_container: ThemeColor,
+    /// Text on the primary container
+    pub on_primary_container: ThemeColor,
+    /// Secondary color
+    pub secondary: ThemeColor,
+    /// Text on the secondary color
+    pub on_secondary: ThemeColor,
+    /// Secondary container
+    pub secondary_container: ThemeColor,
+    /// Text on the secondary container
+    pub on_secondary_container: ThemeColor,
+    /// Tertiary color
+    pub tertiary: ThemeColor,
+    /// Text on the tertiary color
+    pub on_tertiary: ThemeColor,
+    /// Tertiary container
+    pub tertiary_container: ThemeColor,
+    /// Text on the tertiary container
+    pub on_tertiary_container: ThemeColor,
+    /// Background color
+    pub background: ThemeColor,
+    /// Text on the background
+    pub on_background: ThemeColor,
+    /// Surface variant (secondary surfaces)
+    pub surface_variant: ThemeColor,
+    /// Text on the surface variant
+    pub on_surface_variant: ThemeColor,
+    /// Outline (borders and dividers)
+    pub outline: ThemeColor,
+}
+
+impl TonalScheme {
+    /// Generate the tonal palettes of a seed color.
+    ///
+    /// The primary palette keeps the seed chroma, so the accent stays recognizable.
+    pub fn from_seed(seed: ThemeColor) -> Self {
+        let TonalPalette { hue, chroma } = TonalPalette::from_color(seed);
+
+        Self {
+            primary: TonalPalette::new(hue, chroma),
+            secondary: TonalPalette::new(hue, chroma * SECONDARY_CHROMA),
+            tertiary: TonalPalette::new(hue + TERTIARY_HUE_SHIFT, chroma * TERTIARY_CHROMA),
+            neutral: TonalPalette::new(hue, chroma * NEUTRAL_CHROMA),
+            neutral_variant: TonalPalette::new(hue, chroma * NEUTRAL_VARIANT_CHROMA),
+        }
+    }
+
+    /// Get the color roles for a scheme (using the Material 3 tones).
+    pub fn roles(&self, scheme: ThemeScheme) -> TonalRoles {
+        // Tones of the accent, text on it, container and text on the container
+        let (accent, on_accent, container, on_container) = match scheme {
+            ThemeScheme::Light => (40.0, 100.0, 90.0, 10.0),
+            ThemeScheme::Dark => (80.0, 20.0, 30.0, 90.0),
+        };
+        let (background, on_background, surface_variant, on_surface_variant, outline) = match scheme
+        {
+            ThemeScheme::Light => (98.0, 10.0, 90.0, 30.0, 50.0),
+            ThemeScheme::Dark => (6.0, 90.0, 30.0, 80.0, 60.0),
+        };
+
+        TonalRoles {
+            primary: self.primary.tone(accent),
+            on_primary: self.primary.tone(on_accent),
+            primary_container: self.primary.tone(container),
+            on_primary_container: self.primary.tone(on_container),
+            secondary: self.secondary.tone(accent),
+            on_secondary: self.secondary.tone(on_accent),
+            secondary_container: self.secondary.tone(container),
+            on_secondary_container: self.secondary.tone(on_container),
+            tertiary: self.tertiary.tone(accent),
+            on_tertiary: self.tertiary.tone(on_accent),
+            tertiary_container: self.tertiary.tone(container),
+            on_tertiary_container: self.tertiary.tone(on_container),
+            background: self.neutral.tone(background),
+            on_background: self.neutral.tone(on_background),
+            surface_variant: self.neutral_variant.tone(surface_variant),
+            on_surface_variant: self.neutral_variant.tone(on_surface_variant),
+            outline: self.neutral_variant.tone(outline),
+        }
+    }
+}
+
+impl ThemeColor {
+    /// Rotate the hue toward another color's, by half the difference (15 degrees at most).
+    ///
+    /// Used to make fixed colors (e.g. status colors) blend with an accent color. Nothing changes
+    /// if the other color is gray.
+    pub fn harmonize(self, other: ThemeColor) -> Self {
+        let color = self.to_oklch();
+        let target = other.to_oklch();
+        if target.chroma < ACHROMATIC_CHROMA {
+            return self;
+        }
+
+        // Shortest signed hue difference (-180 - 180)
+        let difference = (target.hue - color.hue + 180.0).rem_euclid(360.0) - 180.0;
+        let rotation = (difference / 2.0).clamp(-HARMONIZE_MAX_ROTATION, HARMONIZE_MAX_ROTATION);
+
+        Self::from_oklch_mapped(Oklch {
+            hue: (color.hue + rotation).rem_euclid(360.0),
+            ..color
+        })
+    }
+}
+
+impl ThemePalette {
+    /// Generate a complete palette from a seed color (e.g. the system accent color).
+    ///
+    /// Surfaces and text are tinted neutrals, the accent is the primary role (with its container
+    /// for selections), visited links use the tertiary role and status colors are harmonized with
+    /// the seed.
+    pub fn from_seed(seed: ThemeColor, scheme: ThemeScheme) -> Self {
+        let tonal = TonalScheme::from_seed(seed);
+        let roles = tonal.roles(scheme);
+
+        // Shades are evenly spaced tones between the accent and white or black
+        let (accent, status) = match scheme {
+            ThemeScheme::Light => (40.0, 40.0),
+            ThemeScheme::Dark => (80.0, 80.0),
+        };
+        let lighter = |step: f32| tonal.primary.tone(accent + (100.0 - accent) * step / 4.0);
+        let darker = |step: f32| tonal.primary.tone(accent - accent * step / 4.0);
+        let status_color =
+            |source: ThemeColor| TonalPalette::from_color(source.harmonize(seed)).tone(status);
+
+        // Neutral tones of the view, sidebar, header bar, card, tooltip and tooltip text
+        let surfaces = match scheme {
+            ThemeScheme::Light => [100.0, 96.0, 94.0, 100.0, 20.0, 95.0],
+            ThemeScheme::Dark => [4.0, 10.0, 12.0, 17.0, 90.0, 20.0],
+        };
+        let [view, sidebar, headerbar, card, tooltip, tooltip_foreground] =
+            surfaces.map(|tone| tonal.neutral.tone(tone));
+        // Borders use the outline variant tone
+        let border = match scheme {
+            ThemeScheme::Light => 80.0,
+            ThemeScheme::Dark => 30.0,
+        };
+
+        Self {
+            background: roles.background,
+            foreground: roles.on_background,
+            view,
+            sidebar,
+            headerbar,
+            card,
+            border: tonal.neutral_variant.tone(border),
+            muted_foreground: roles.on_surface_variant,
+            disabled_foreground: roles
+                .on_background
+                .mix(roles.background, 1.0 - DISABLED_OPACITY),
+            accent: roles.primary,
+            accent_shades: ThemeAccentShades {
+                light1: lighter(1.0),
+                light2: lighter(2.0),
+                light3: lighter(3.0),
+                dark1: darker(1.0),
+                dark2: darker(2.0),
+                dark3: darker(3.0),
+            },
+            accent_foreground: roles.on_primary,
+            selection: roles.primary_container,
+            selection_foreground: roles.on_primary_container,
+            focus_ring: roles.primary,
+            link: roles.primary,
+            visited_link: roles.tertiary,
+            tooltip,
+            tooltip_foreground,
+            success: status_color(SUCCESS_SOURCE),
+            warning: status_color(WARNING_SOURCE),
+            danger: status_color(DANGER_SOURCE),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Theme, ThemeContrast};
+
+    const SEEDS: [ThemeColor; 4] = [
+        ThemeColor::from_rgb8(0, 120, 212),
+        ThemeColor::from_rgb8(233, 84, 32),
+        ThemeColor::from_rgb8(58, 148, 74),
+        ThemeColor::from_rgb8(128, 128, 128),
+    ];
+
+    /// Shortest distance between two hues (in degrees)
+    fn hue_distance(first: f32, second: f32) -> f32 {
+        let difference = (first - second).rem_euclid(360.0);
+        difference.min(360.0 - difference)
+    }
+
+    #[test]
+    fn tone_targets() {
+        for seed in SEEDS {
+            let palette = TonalPalette::from_color(seed);
+
+            assert_eq!(palette.tone(0.0).to_rgba8(), [0, 0, 0, 255], "{seed}");
+            assert_eq!(
+                palette.tone(100.0).to_rgba8(),
+                [255, 255, 255, 255],
+                "{seed}"
+            );
+
+            // Tones match the CIELAB lightness (tone 50 is 18.4% luminance)
+            let luminance = palette.tone(50.0).relative_luminance();
+            assert!((luminance - 0.1842).abs() < 0.005, "{seed} {luminance}");
+        }
+    }
+
+    #[test]
+    fn monotonic_tones() {
+        for seed in SEEDS {
+            let palette = TonalPalette::from_color(seed);
+            let luminances: Vec<f32> = (0..=20)
+                .map(|step| palette.tone(step as f32 * 5.0).relative_luminance())
+                .collect();
+
+            for pair in luminances.windows(2) {
+                assert!(pair[0] < pair[1], "{seed} {luminances:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn seed_hue() {
+        for seed in SEEDS.into_iter().take(3) {
+            let hue = seed.to_oklch().hue;
+            let scheme = TonalScheme::from_seed(seed);
+
+            for palette in [
+                scheme.primary,
+                scheme.secondary,
+                scheme.neutral,
+                scheme.neutral_variant,
+            ] {
+                assert!(hue_distance(palette.hue, hue) < 0.01, "{seed}");
+            }
+            assert!(hue_distance(scheme.tertiary.hue, hue + TERTIARY_HUE_SHIFT) < 0.01);
+
+            // Colors of the primary palette keep the hue (gamut mapping only reduces chroma)
+            for tone in [30.0, 40.0, 80.0, 90.0] {
+                let color = scheme.primary.tone(tone).to_oklch();
+                assert!(hue_distance(color.hue, hue) < 3.0, "{seed} tone {tone}");
+            }
+        }
+    }
+
+    #[test]
+    fn harmonize_rotation() {
+        let sources = [SUCCESS_SOURCE, WARNING_SOURCE, DANGER_SOURCE];
+
+        for seed in SEEDS.into_iter().take(3) {
+            let target = seed.to_oklch().hue;
+
+            for source in sources {
+                let original = source.to_oklch().hue;
+                let harmonized = source.harmonize(seed).to_oklch().hue;
+
+                // Half the way to the seed hue, by the limit at most
+                let rotation = hue_distance(harmonized, original);
+                let expected = (hue_distance(original, target) / 2.0).min(HARMONIZE_MAX_ROTATION);
+                assert!((rotation - expected).abs() < 1.0, "{source} to {seed}");
+                assert!(
+                    rotation <= HARMONIZE_MAX_ROTATION + 0.5,
+                    "{source} to {seed}"
+                );
+                assert!(hue_distance(harmonized, target) <= hue_distance(original, target));
+            }
+        }
+
+        // Gray has no hue to rotate toward
+        let gray = ThemeColor::from_rgb8(128, 128, 128);
+        assert_eq!(SUCCESS_SOURCE.harmonize(gray), SUCCESS_SOURCE);
+    }
+
+    #[test]
+    fn tonal_accent_contrast() {
+        for seed in SEEDS {
+            for scheme in [ThemeScheme::Light, ThemeScheme::Dark] {
+                for contrast in [ThemeContrast::Normal, ThemeContrast::High] {
+                    let palette = Theme::tonal(seed, scheme, contrast).palette;
+                    let ratio = palette.accent_foreground.contrast_ratio(palette.accent);
+                    assert!(ratio >= 4.5, "{seed} {scheme:?} {contrast:?} is {ratio}:1");
+
+                    let ratio = palette.foreground.contrast_ratio(palette.background);
+                    assert!(ratio >= 7.0, "{seed} {scheme:?} {contrast:?} is {ratio}:1");
+                }
+            }
+        }
+    }
+}